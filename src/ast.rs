@@ -0,0 +1,1748 @@
+use std::collections::HashMap;
+
+use pest::iterators::{Pair, Pairs};
+
+use crate::{parse_by_rule, ErrorParse, ParseOptions, ParseWarning, Rule, SyntaxError};
+
+/// A position in the markdown input.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Position {
+    /// The line number, starting from 1.
+    pub line: usize,
+    /// The column number in characters, starting from 1.
+    pub column: usize,
+    /// The byte offset from the start of the input.
+    pub offset: usize,
+}
+
+/// A range of the markdown input from which a node was parsed.
+/// The start position is inclusive and the end position is exclusive.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Span {
+    /// The position of the first character of the node.
+    pub start: Position,
+    /// The position right after the last character of the node.
+    pub end: Position,
+}
+
+/// A parsed markdown document, the root of the document tree.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Document {
+    /// Top-level blocks of the document in the order they appear in the text.
+    pub blocks: Vec<Block>,
+    /// The range of the whole document.
+    pub span: Span,
+}
+
+/// A block-level element of a markdown document with its location in the input.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Block {
+    /// The kind and the content of the block.
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub kind: BlockKind,
+    /// The range of the block, without the trailing line break.
+    pub span: Span,
+}
+
+/// Kinds of block-level elements of a markdown document.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(tag = "type", content = "value", rename_all = "snake_case")
+)]
+pub enum BlockKind {
+    /// An ATX (`# Heading`) or setext (`Heading` underlined with `===`) heading of level 1 to 6.
+    Heading { level: u8, children: Vec<Inline> },
+    /// A paragraph of one or more lines, separated by `InlineKind::LineBreak`.
+    Paragraph { children: Vec<Inline> },
+    /// A fenced code block with an optional language and its literal content.
+    CodeBlock {
+        lang: Option<String>,
+        content: String,
+    },
+    /// A blockquote, which may contain any blocks.
+    Quote { children: Vec<Block> },
+    /// An ordered or unordered list.
+    List(List),
+    /// A table with a header row and body rows, every row has as many cells as the header.
+    Table {
+        alignments: Vec<Alignment>,
+        header: Vec<TableCell>,
+        rows: Vec<Vec<TableCell>>,
+    },
+    /// A horizontal rule (`---`).
+    HorizontalRule,
+    /// An empty line between blocks.
+    EmptyLine,
+    /// A footnote definition (`[^label]: text`), which is rendered where the footnotes of a document are listed.
+    FootnoteDefinition {
+        label: String,
+        children: Vec<Inline>,
+    },
+    /// A link reference definition (`[label]: url "title"`), which is already resolved in links and images.
+    LinkDefinition {
+        label: String,
+        url: String,
+        title: Option<String>,
+    },
+}
+
+/// An ordered (`1. item`) or unordered (`- item`) list.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct List {
+    /// Whether the items of the list are numbered.
+    pub ordered: bool,
+    /// The number of the first item of an ordered list, `1` for unordered lists.
+    pub start: u64,
+    /// Items of the list.
+    pub items: Vec<ListItem>,
+    /// The range of the list.
+    pub span: Span,
+}
+
+/// A single item of a list.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ListItem {
+    /// The state of the checkbox of a task item, or `None` if the item is not a task.
+    pub checked: Option<bool>,
+    /// The text of the item.
+    pub children: Vec<Inline>,
+    /// A list nested inside the item.
+    pub nested: Option<List>,
+    /// Blocks of the item after its text and nested list: indented blocks separated from the text by an empty line,
+    /// and nested lists following the first one with another marker.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub blocks: Vec<Block>,
+    /// The range of the item, including its nested list and blocks.
+    pub span: Span,
+}
+
+/// A single cell of a table.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TableCell {
+    /// The text of the cell.
+    pub children: Vec<Inline>,
+    /// The range of the cell, empty at the end of the row for cells missing in the input.
+    pub span: Span,
+}
+
+/// The alignment of a table column, defined by its delimiter row (e.g., `:---:`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum Alignment {
+    None,
+    Left,
+    Center,
+    Right,
+}
+
+/// An inline element of a markdown document with its location in the input.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Inline {
+    /// The kind and the content of the inline element.
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub kind: InlineKind,
+    /// The range of the inline element.
+    pub span: Span,
+}
+
+/// Kinds of inline elements of a markdown document.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(tag = "type", content = "value", rename_all = "snake_case")
+)]
+pub enum InlineKind {
+    /// Literal text, with escaped characters already unescaped.
+    Text(String),
+    /// An inline code span (`` `code` ``) with its literal content.
+    Code(String),
+    /// Bold text (`**bold**`).
+    Strong(Vec<Inline>),
+    /// Italic text (`*italic*` or `_italic_`).
+    Emphasis(Vec<Inline>),
+    /// Strikethrough text (`~~strikethrough~~`).
+    Strikethrough(Vec<Inline>),
+    /// Underlined text (`__underline__`).
+    Underline(Vec<Inline>),
+    /// An inline or a resolved reference link.
+    Link {
+        url: String,
+        title: Option<String>,
+        children: Vec<Inline>,
+    },
+    /// An inline or a resolved reference image.
+    Image {
+        url: String,
+        title: Option<String>,
+        alt: String,
+    },
+    /// A reference to a defined footnote (`[^label]`).
+    FootnoteReference { label: String },
+    /// A line break between lines of a paragraph.
+    LineBreak,
+}
+
+/// Maps byte offsets of a parsed text to byte offsets of the markdown input.
+/// The content of a blockquote is parsed without its `>` markers, so every line of it is mapped separately.
+#[derive(Debug, Clone)]
+struct SourceMap {
+    /// Start offsets of the mapped lines in the parsed text, with their offsets in the markdown input.
+    segments: Vec<(usize, usize)>,
+}
+
+impl SourceMap {
+    /// Creates a map of a text which is the markdown input itself.
+    fn identity() -> Self {
+        SourceMap {
+            segments: vec![(0, 0)],
+        }
+    }
+
+    /// Maps the `offset` in the parsed text to the offset in the markdown input.
+    fn offset(&self, offset: usize) -> usize {
+        let index = self
+            .segments
+            .partition_point(|(start, _)| *start <= offset)
+            .saturating_sub(1);
+        let (start, original) = self.segments[index];
+
+        original + offset - start
+    }
+
+    /// Creates a map of a part of the parsed text which starts at the offset `start`.
+    fn shifted(&self, start: usize) -> SourceMap {
+        let mut segments = vec![(0, self.offset(start))];
+        segments.extend(
+            self.segments
+                .iter()
+                .filter(|(segment_start, _)| *segment_start > start)
+                .map(|(segment_start, original)| (segment_start - start, *original)),
+        );
+
+        SourceMap { segments }
+    }
+}
+
+/// State of a markdown document shared between rules while its tree is built.
+struct BuildContext<'s> {
+    /// The markdown input.
+    source: &'s str,
+    /// Byte offsets at which the lines of the markdown input start.
+    line_starts: Vec<usize>,
+    /// Footnote definitions of the document by their normalized label, with their label and line.
+    footnote_definitions: HashMap<String, (String, usize)>,
+    /// Link reference definitions of the document by their normalized label, with their destination and title.
+    link_definitions: HashMap<String, (String, Option<String>)>,
+    /// Warnings collected while building the tree.
+    warnings: Vec<ParseWarning>,
+    /// Options which define the parsed markdown constructs.
+    options: ParseOptions,
+}
+
+impl<'s> BuildContext<'s> {
+    /// Creates the state of the document for the markdown input `source`.
+    fn new(source: &'s str, options: ParseOptions) -> Self {
+        let line_starts = std::iter::once(0)
+            .chain(source.match_indices('\n').map(|(i, _)| i + 1))
+            .collect();
+
+        BuildContext {
+            source,
+            line_starts,
+            footnote_definitions: HashMap::new(),
+            link_definitions: HashMap::new(),
+            warnings: Vec::new(),
+            options,
+        }
+    }
+
+    /// Returns the position of the byte `offset` of the markdown input.
+    fn position(&self, offset: usize) -> Position {
+        let line = self.line_starts.partition_point(|start| *start <= offset);
+        let line_start = self.line_starts[line - 1];
+
+        Position {
+            line,
+            column: self.source[line_start..offset].chars().count() + 1,
+            offset,
+        }
+    }
+
+    /// Returns the span between the `start` and `end` offsets of a parsed text, mapped to the markdown input.
+    fn span(&self, map: &SourceMap, start: usize, end: usize) -> Span {
+        Span {
+            start: self.position(map.offset(start)),
+            end: self.position(map.offset(end)),
+        }
+    }
+
+    /// Returns the span of a parsed `pair` without its trailing line breaks, unless it consists only of them.
+    fn pair_span(&self, map: &SourceMap, pair: &Pair<Rule>) -> Span {
+        let text = pair.as_str();
+        let trimmed = text.trim_end_matches(['\r', '\n']);
+        let length = match trimmed.is_empty() {
+            true => text.len(),
+            false => trimmed.len(),
+        };
+        let start = pair.as_span().start();
+
+        self.span(map, start, start + length)
+    }
+}
+
+/// Parses the markdown `text` and builds its document tree.
+/// Reference links and images are resolved against link definitions of the whole document.
+///
+/// # Arguments
+/// * `text` - A string slice containing the markdown content.
+/// * `options` - Options which define the parsed markdown constructs.
+///
+/// # Errors
+/// - Returns an `ErrorParse::ParsingError` if there's an error during a parsing process.
+/// - Returns an `ErrorParse::SyntaxError` for the first unmatched delimiter if the options are strict.
+///
+/// # Returns
+/// A result containing a tuple of the document and warnings about it.
+pub(crate) fn build_document(
+    text: &str,
+    options: &ParseOptions,
+) -> Result<(Document, Vec<ParseWarning>), ErrorParse> {
+    let markdown_content = parse_by_rule(Rule::markdown, text)?
+        .next()
+        .ok_or_else(|| ErrorParse::ParsingError(String::from("Expected markdown got nothing")))?;
+
+    let map = SourceMap::identity();
+    let mut ctx = BuildContext::new(text, *options);
+    collect_definitions(markdown_content.clone().into_inner(), &map, &mut ctx)?;
+
+    let document = Document {
+        span: ctx.span(&map, 0, text.len()),
+        blocks: parse_blocks(markdown_content.into_inner(), &map, &mut ctx)?,
+    };
+
+    let mut warnings = std::mem::take(&mut ctx.warnings);
+    let referenced = referenced_footnotes(&document);
+    let mut unused: Vec<&(String, usize)> = ctx
+        .footnote_definitions
+        .iter()
+        .filter(|(label, _)| !referenced.contains(label))
+        .map(|(_, definition)| definition)
+        .collect();
+    unused.sort_by_key(|(_, line)| *line);
+
+    for (label, line) in unused {
+        warnings.push(ParseWarning::UnusedFootnote {
+            label: label.clone(),
+            line: *line,
+        });
+    }
+
+    Ok((document, warnings))
+}
+
+/// Collects footnote and link reference definitions from the parsed `pairs`, including the ones inside blockquotes and list items.
+/// If several definitions have the same label, the first one is used. Disabled constructs are skipped.
+///
+/// # Arguments
+/// * `pairs` - Parsed `pest::iterators::Pairs` of a markdown document.
+/// * `map` - The map of the parsed text to the markdown input.
+/// * `ctx` - The state of the document which is being built.
+///
+/// # Errors
+/// - Returns an `ErrorParse::ParsingError` if there's an error during a parsing process.
+///
+/// # Returns
+/// A result indicating success or an `ErrorParse` if the parsing of a blockquote fails.
+fn collect_definitions(
+    pairs: Pairs<Rule>,
+    map: &SourceMap,
+    ctx: &mut BuildContext,
+) -> Result<(), ErrorParse> {
+    for pair in pairs.flatten() {
+        if !ctx.options.allows(pair.as_rule()) {
+            continue;
+        }
+
+        match pair.as_rule() {
+            Rule::footnote_definition => {
+                let label = footnote_definition_label(&pair);
+                let line = ctx.pair_span(map, &pair).start.line;
+                ctx.footnote_definitions
+                    .entry(normalize_label(label))
+                    .or_insert((String::from(label), line));
+            }
+            Rule::link_definition => {
+                let (label, destination, title) = parse_link_definition(pair)?;
+                ctx.link_definitions
+                    .entry(normalize_label(&label))
+                    .or_insert((destination, title));
+            }
+            Rule::quote => {
+                let quote_map = quote_source_map(&pair, map);
+                let content = quote_content(pair);
+                let pairs = parse_content(Rule::markdown, &content, &quote_map, ctx)?;
+                collect_definitions(pairs, &quote_map, ctx)?;
+            }
+            Rule::list_item_content if ctx.options.allows(Rule::unordered_list) => {
+                let (content, content_map) = list_item_content(pair, map);
+                let pairs = parse_content(Rule::markdown, &content, &content_map, ctx)?;
+                collect_definitions(pairs, &content_map, ctx)?;
+            }
+            _ => {}
+        }
+    }
+
+    Ok(())
+}
+
+/// Builds the blocks of a markdown document from the parsed `pairs`.
+/// Disabled blocks don't interrupt a paragraph, so their literal text is joined with the adjacent paragraphs.
+///
+/// # Arguments
+/// * `pairs` - Parsed `pest::iterators::Pairs` of a markdown document.
+/// * `map` - The map of the parsed text to the markdown input.
+/// * `ctx` - The state of the document which is being built.
+///
+/// # Errors
+/// - Returns an `ErrorParse::ParsingError` if there's an error during a parsing process.
+///
+/// # Returns
+/// A result containing the blocks of the document.
+fn parse_blocks(
+    pairs: Pairs<Rule>,
+    map: &SourceMap,
+    ctx: &mut BuildContext,
+) -> Result<Vec<Block>, ErrorParse> {
+    let mut blocks: Vec<Block> = Vec::new();
+    let mut previous = None;
+
+    for pair in pairs.filter(|pair| pair.as_rule() != Rule::EOI) {
+        let literal = !ctx.options.allows(pair.as_rule());
+        let current = match literal || pair.as_rule() == Rule::paragraph {
+            true => Some(literal),
+            false => None,
+        };
+        let block = parse_block(pair, map, ctx)?;
+
+        match (previous, current, blocks.last_mut()) {
+            (Some(previous_literal), Some(literal), Some(last)) if previous_literal || literal => {
+                join_paragraphs(last, block);
+                previous = Some(true);
+            }
+            _ => {
+                blocks.push(block);
+                previous = current;
+            }
+        }
+    }
+
+    Ok(blocks)
+}
+
+/// Appends the inline elements of the paragraph `block` to the paragraph `last`, separated by a line break.
+///
+/// # Arguments
+/// * `last` - The paragraph which is continued.
+/// * `block` - The paragraph which follows `last` without an empty line between them.
+fn join_paragraphs(last: &mut Block, block: Block) {
+    if let (BlockKind::Paragraph { children }, BlockKind::Paragraph { children: appended }) =
+        (&mut last.kind, block.kind)
+    {
+        children.push(Inline {
+            kind: InlineKind::LineBreak,
+            span: Span {
+                start: last.span.end,
+                end: block.span.start,
+            },
+        });
+        for inline in appended {
+            push_inline(children, inline);
+        }
+        last.span.end = block.span.end;
+    }
+}
+
+/// Builds a single block from a `pest::iterators::Pair` representing a block rule.
+/// A disabled block is built as a paragraph with its literal text.
+///
+/// # Arguments
+/// * `pair` - A `pest::iterators::Pair` representing the parsed block.
+/// * `map` - The map of the parsed text to the markdown input.
+/// * `ctx` - The state of the document which is being built.
+///
+/// # Errors
+/// - Returns an `ErrorParse::ParsingError` if the rule is not a block or an error occurs.
+///
+/// # Returns
+/// A result containing the block.
+fn parse_block(
+    pair: Pair<Rule>,
+    map: &SourceMap,
+    ctx: &mut BuildContext,
+) -> Result<Block, ErrorParse> {
+    let span = ctx.pair_span(map, &pair);
+
+    if !ctx.options.allows(pair.as_rule()) {
+        return Ok(Block {
+            kind: BlockKind::Paragraph {
+                children: parse_literal_block(pair, map, ctx)?,
+            },
+            span,
+        });
+    }
+
+    let kind = match pair.as_rule() {
+        Rule::empty_line => BlockKind::EmptyLine,
+        Rule::heading1
+        | Rule::heading2
+        | Rule::heading3
+        | Rule::heading4
+        | Rule::heading5
+        | Rule::heading6 => {
+            let level = heading_level(pair.as_rule());
+            let children = match pair.into_inner().next() {
+                Some(single_line_text) => parse_inlines(single_line_text.into_inner(), map, ctx)?,
+                None => Vec::new(),
+            };
+            BlockKind::Heading { level, children }
+        }
+        Rule::setext_heading1 | Rule::setext_heading2 => BlockKind::Heading {
+            level: heading_level(pair.as_rule()),
+            children: parse_setext_heading(pair, map, ctx)?,
+        },
+        Rule::code_block => parse_code_block(pair)?,
+        Rule::quote => BlockKind::Quote {
+            children: parse_quote(pair, map, ctx)?,
+        },
+        Rule::horizontal_rule => BlockKind::HorizontalRule,
+        Rule::paragraph => BlockKind::Paragraph {
+            children: parse_paragraph(pair, map, ctx)?,
+        },
+        Rule::unordered_list | Rule::ordered_list => BlockKind::List(parse_list(pair, map, ctx)?),
+        Rule::table => parse_table(pair, map, ctx)?,
+        Rule::footnote_definition => {
+            let label = String::from(footnote_definition_label(&pair));
+            let text = pair
+                .into_inner()
+                .find(|inner| inner.as_rule() == Rule::footnote_text)
+                .ok_or_else(|| {
+                    ErrorParse::ParsingError(String::from(
+                        "Expected footnote_text rule inside a footnote_definition",
+                    ))
+                })?;
+            BlockKind::FootnoteDefinition {
+                label,
+                children: parse_inlines(text.into_inner(), map, ctx)?,
+            }
+        }
+        Rule::link_definition => {
+            let (label, url, title) = parse_link_definition(pair)?;
+            BlockKind::LinkDefinition { label, url, title }
+        }
+        _ => {
+            return Err(ErrorParse::ParsingError(format!(
+                "Unknown rule: {:#?}",
+                pair.as_rule()
+            )))
+        }
+    };
+
+    Ok(Block { kind, span })
+}
+
+/// Returns the level of a heading rule, e.g. `2` for `heading2` and `setext_heading2`.
+///
+/// # Arguments
+/// * `rule` - The heading rule.
+///
+/// # Returns
+/// The level of the heading.
+fn heading_level(rule: Rule) -> u8 {
+    match rule {
+        Rule::heading2 | Rule::setext_heading2 => 2,
+        Rule::heading3 => 3,
+        Rule::heading4 => 4,
+        Rule::heading5 => 5,
+        Rule::heading6 => 6,
+        _ => 1,
+    }
+}
+
+/// Builds inline elements from the parsed `pairs` of a text, merging adjacent text into a single `InlineKind::Text`.
+/// Disabled inline elements are kept as literal text.
+///
+/// # Arguments
+/// * `pairs` - Parsed `pest::iterators::Pairs` of inline rules.
+/// * `map` - The map of the parsed text to the markdown input.
+/// * `ctx` - The state of the document which is being built.
+///
+/// # Errors
+/// - Returns an `ErrorParse::ParsingError` if there's an error during a parsing process.
+///
+/// # Returns
+/// A result containing the inline elements.
+fn parse_inlines(
+    pairs: Pairs<Rule>,
+    map: &SourceMap,
+    ctx: &mut BuildContext,
+) -> Result<Vec<Inline>, ErrorParse> {
+    let mut inlines = Vec::new();
+
+    for pair in pairs {
+        if !ctx.options.allows(pair.as_rule()) {
+            for inline in parse_literal_inline(pair, map, ctx)? {
+                push_inline(&mut inlines, inline);
+            }
+            continue;
+        }
+
+        let inline = parse_inline(pair, map, ctx)?;
+        push_inline(&mut inlines, inline);
+    }
+
+    Ok(inlines)
+}
+
+/// Builds a disabled inline element as literal text.
+/// The delimiters of disabled underlined and strikethrough text are kept as text, while its content is still parsed.
+///
+/// # Arguments
+/// * `pair` - A `pest::iterators::Pair` representing the disabled inline element.
+/// * `map` - The map of the parsed text to the markdown input.
+/// * `ctx` - The state of the document which is being built.
+///
+/// # Errors
+/// - Returns an `ErrorParse::ParsingError` if there's an error during a parsing process.
+///
+/// # Returns
+/// A result containing the inline elements which replace the disabled element.
+fn parse_literal_inline(
+    pair: Pair<Rule>,
+    map: &SourceMap,
+    ctx: &mut BuildContext,
+) -> Result<Vec<Inline>, ErrorParse> {
+    let span = pair.as_span();
+    let text = |start: usize, end: usize| Inline {
+        kind: InlineKind::Text(String::from(
+            &pair.as_str()[start - span.start()..end - span.start()],
+        )),
+        span: ctx.span(map, start, end),
+    };
+
+    if !matches!(pair.as_rule(), Rule::underline | Rule::strikethrough) {
+        return Ok(vec![text(span.start(), span.end())]);
+    }
+
+    let delimiter_length = 2;
+    let opening = text(span.start(), span.start() + delimiter_length);
+    let closing = text(span.end() - delimiter_length, span.end());
+
+    let mut inlines = vec![opening];
+    inlines.extend(parse_inlines(pair.into_inner(), map, ctx)?);
+    inlines.push(closing);
+
+    Ok(inlines)
+}
+
+/// Adds the `inline` element to `inlines`, appending text to the preceding text element if there is one.
+///
+/// # Arguments
+/// * `inlines` - The inline elements to which the element is added.
+/// * `inline` - The added element.
+fn push_inline(inlines: &mut Vec<Inline>, inline: Inline) {
+    if let Some(last) = inlines.last_mut() {
+        if let (InlineKind::Text(last_text), InlineKind::Text(text)) =
+            (&mut last.kind, &inline.kind)
+        {
+            last_text.push_str(text);
+            last.span.end = inline.span.end;
+            return;
+        }
+    }
+
+    inlines.push(inline);
+}
+
+/// Builds a single inline element from a `pest::iterators::Pair` representing an inline rule.
+///
+/// # Arguments
+/// * `pair` - A `pest::iterators::Pair` representing the parsed inline element.
+/// * `map` - The map of the parsed text to the markdown input.
+/// * `ctx` - The state of the document which is being built.
+///
+/// # Errors
+/// - Returns an `ErrorParse::ParsingError` if the rule is not an inline element or an error occurs.
+/// - Returns an `ErrorParse::SyntaxError` if the element is an unmatched delimiter and the document is built in strict mode.
+///
+/// # Returns
+/// A result containing the inline element.
+fn parse_inline(
+    pair: Pair<Rule>,
+    map: &SourceMap,
+    ctx: &mut BuildContext,
+) -> Result<Inline, ErrorParse> {
+    let span = ctx.pair_span(map, &pair);
+
+    let kind = match pair.as_rule() {
+        Rule::plain_text
+        | Rule::content
+        | Rule::heading_text
+        | Rule::table_text
+        | Rule::link_plain_text
+        | Rule::literal_char
+        | Rule::unmatched_backticks => InlineKind::Text(String::from(pair.as_str())),
+        Rule::unmatched_delimiter if ctx.options.is_strict() => {
+            return Err(ErrorParse::SyntaxError(SyntaxError::new(
+                ctx.source,
+                span.start.offset,
+                vec![format!("closing `{}`", pair.as_str())],
+            )))
+        }
+        Rule::unmatched_delimiter => InlineKind::Text(String::from(pair.as_str())),
+        Rule::escaped => InlineKind::Text(parse_escaped_char(pair)?),
+        Rule::code_span => InlineKind::Code(parse_code_span(pair)?),
+        Rule::bold => InlineKind::Strong(parse_inlines(pair.into_inner(), map, ctx)?),
+        Rule::italic => InlineKind::Emphasis(parse_inlines(pair.into_inner(), map, ctx)?),
+        Rule::strikethrough => {
+            InlineKind::Strikethrough(parse_inlines(pair.into_inner(), map, ctx)?)
+        }
+        Rule::underline => InlineKind::Underline(parse_inlines(pair.into_inner(), map, ctx)?),
+        Rule::inline_link | Rule::inline_image => parse_inline_link(pair, map, ctx)?,
+        Rule::reference_link | Rule::reference_image => parse_reference(pair, map, ctx)?,
+        Rule::link_line_break => InlineKind::LineBreak,
+        Rule::footnote_reference => parse_footnote_reference(pair, span, ctx)?,
+        _ => {
+            return Err(ErrorParse::ParsingError(format!(
+                "Unknown rule: {:#?}",
+                pair.as_rule()
+            )))
+        }
+    };
+
+    Ok(Inline { kind, span })
+}
+
+/// Parses an escaped character from the markdown syntax, such as escaped special characters.
+///
+/// # Arguments
+/// * `pair` - A `pest::iterators::Pair` representing the escaped character rule.
+///
+/// # Errors
+/// - Returns an `ErrorParse::ParsingError` if there's an error during a parsing process.
+///
+/// # Returns
+/// A result containing the escaped character.
+fn parse_escaped_char(pair: Pair<Rule>) -> Result<String, ErrorParse> {
+    let mut inner = pair.into_inner();
+    let char_sym = inner
+        .next()
+        .ok_or_else(|| {
+            ErrorParse::ParsingError(String::from("Expected char rule inside an escaped"))
+        })?
+        .as_str();
+
+    Ok(String::from(char_sym))
+}
+
+/// Parses an inline code span (e.g., `` `code` ``) from the markdown input.
+/// If the content both begins and ends with a space, but doesn't consist only of spaces, a single space is stripped from each side.
+///
+/// # Arguments
+/// * `pair` - A `pest::iterators::Pair` representing the code span rule.
+///
+/// # Errors
+/// - Returns an `ErrorParse::ParsingError` if there's an error during a parsing process.
+///
+/// # Returns
+/// A result containing the literal content of the code span.
+fn parse_code_span(pair: Pair<Rule>) -> Result<String, ErrorParse> {
+    let mut inner = pair.into_inner();
+    let mut content = inner
+        .next()
+        .ok_or_else(|| {
+            ErrorParse::ParsingError(String::from(
+                "Expected code_span_content rule inside a code_span",
+            ))
+        })?
+        .as_str();
+
+    if content.len() > 2
+        && content.starts_with(' ')
+        && content.ends_with(' ')
+        && !content.chars().all(|c| c == ' ')
+    {
+        content = &content[1..content.len() - 1];
+    }
+
+    Ok(String::from(content))
+}
+
+/// Parses an inline link (e.g., `[link_text](url "title")`) or an inline image (e.g., `![alt_text](url "title")`).
+///
+/// # Arguments
+/// * `pair` - A `pest::iterators::Pair` representing the inline link or image rule.
+/// * `map` - The map of the parsed text to the markdown input.
+/// * `ctx` - The state of the document which is being built.
+///
+/// # Errors
+/// - Returns an `ErrorParse::ParsingError` if there's an error during a parsing process.
+///
+/// # Returns
+/// A result containing an `InlineKind::Link` or an `InlineKind::Image`.
+fn parse_inline_link(
+    pair: Pair<Rule>,
+    map: &SourceMap,
+    ctx: &mut BuildContext,
+) -> Result<InlineKind, ErrorParse> {
+    let is_image = pair.as_rule() == Rule::inline_image;
+    let mut inner = pair.into_inner();
+    let text = inner.next().ok_or_else(|| {
+        ErrorParse::ParsingError(String::from(
+            "Expected link_text or alt_text rule inside an inline link",
+        ))
+    })?;
+    let url = inner.next().ok_or_else(|| {
+        ErrorParse::ParsingError(String::from("Expected url inside an inline link"))
+    })?;
+    let url = String::from(parse_link_destination(&url));
+    let title = inner.next().map(parse_link_title);
+
+    if is_image {
+        Ok(InlineKind::Image {
+            url,
+            title,
+            alt: String::from(text.as_str()),
+        })
+    } else {
+        Ok(InlineKind::Link {
+            url,
+            title,
+            children: parse_inlines(text.into_inner(), map, ctx)?,
+        })
+    }
+}
+
+/// Parses a reference link or image (e.g., `[text][label]`, `[text][]` or `[text]`) and resolves it against link definitions.
+/// A reference without a matching definition is kept as plain text.
+///
+/// # Arguments
+/// * `pair` - A `pest::iterators::Pair` representing the reference link or image rule.
+/// * `map` - The map of the parsed text to the markdown input.
+/// * `ctx` - The state of the document which is being built.
+///
+/// # Errors
+/// - Returns an `ErrorParse::ParsingError` if there's an error during a parsing process.
+///
+/// # Returns
+/// A result containing an `InlineKind::Link` or an `InlineKind::Image`, or `InlineKind::Text` with the source of an unresolved reference.
+fn parse_reference(
+    pair: Pair<Rule>,
+    map: &SourceMap,
+    ctx: &mut BuildContext,
+) -> Result<InlineKind, ErrorParse> {
+    let is_image = pair.as_rule() == Rule::reference_image;
+    let source = pair.as_str();
+    let mut inner = pair.into_inner();
+
+    let Some(text) = inner.next() else {
+        return Ok(InlineKind::Text(String::from(source)));
+    };
+    let label = inner
+        .next()
+        .map(|label| label.as_str())
+        .unwrap_or(text.as_str());
+
+    let Some((url, title)) = ctx.link_definitions.get(&normalize_label(label)).cloned() else {
+        return Ok(InlineKind::Text(String::from(source)));
+    };
+
+    if is_image {
+        Ok(InlineKind::Image {
+            url,
+            title,
+            alt: String::from(text.as_str()),
+        })
+    } else {
+        Ok(InlineKind::Link {
+            url,
+            title,
+            children: parse_inlines(text.into_inner(), map, ctx)?,
+        })
+    }
+}
+
+/// Parses a footnote reference (e.g., `[^note]`).
+/// A reference to an undefined footnote is kept as plain text and reported as a warning.
+///
+/// # Arguments
+/// * `pair` - A `pest::iterators::Pair` representing the footnote reference rule.
+/// * `span` - The span of the footnote reference.
+/// * `ctx` - The state of the document which is being built.
+///
+/// # Errors
+/// - Returns an `ErrorParse::ParsingError` if there's an error during a parsing process.
+///
+/// # Returns
+/// A result containing an `InlineKind::FootnoteReference`, or `InlineKind::Text` with the source of an undefined reference.
+fn parse_footnote_reference(
+    pair: Pair<Rule>,
+    span: Span,
+    ctx: &mut BuildContext,
+) -> Result<InlineKind, ErrorParse> {
+    let label = pair
+        .clone()
+        .into_inner()
+        .next()
+        .ok_or_else(|| {
+            ErrorParse::ParsingError(String::from(
+                "Expected footnote_label rule inside a footnote_reference",
+            ))
+        })?
+        .as_str();
+
+    if !ctx
+        .footnote_definitions
+        .contains_key(&normalize_label(label))
+    {
+        ctx.warnings.push(ParseWarning::UndefinedFootnote {
+            label: String::from(label),
+            line: span.start.line,
+        });
+        return Ok(InlineKind::Text(String::from(pair.as_str())));
+    }
+
+    Ok(InlineKind::FootnoteReference {
+        label: String::from(label),
+    })
+}
+
+/// Parses a paragraph from the markdown input.
+/// A paragraph is a collection of paragraph lines, which are separated by `InlineKind::LineBreak`.
+///
+/// # Arguments
+/// * `pair` - A `pest::iterators::Pair` representing the paragraph rule.
+/// * `map` - The map of the parsed text to the markdown input.
+/// * `ctx` - The state of the document which is being built.
+///
+/// # Errors
+/// - Returns an `ErrorParse::ParsingError` if there's an error during a parsing process.
+///
+/// # Returns
+/// A result containing the inline elements of the entire paragraph.
+fn parse_paragraph(
+    pair: Pair<Rule>,
+    map: &SourceMap,
+    ctx: &mut BuildContext,
+) -> Result<Vec<Inline>, ErrorParse> {
+    let mut children = Vec::new();
+    let mut previous_end = None;
+
+    for line in pair.into_inner() {
+        if line.as_rule() != Rule::paragraph_line {
+            return Err(ErrorParse::ParsingError(format!(
+                "Unexpected rule inside a paragraph: {:#?}",
+                line.as_rule()
+            )));
+        }
+
+        if let Some(previous_end) = previous_end {
+            children.push(Inline {
+                kind: InlineKind::LineBreak,
+                span: ctx.span(map, previous_end, line.as_span().start()),
+            });
+        }
+        previous_end = Some(line_end(&line));
+
+        for inline in parse_inlines(line.into_inner(), map, ctx)? {
+            push_inline(&mut children, inline);
+        }
+    }
+
+    Ok(children)
+}
+
+/// Parses a setext heading (e.g., `Heading 1` underlined with `===`) from the markdown input.
+/// Lines of a multi-line heading are joined with a space.
+///
+/// # Arguments
+/// * `pair` - A `pest::iterators::Pair` representing the setext heading rule.
+/// * `map` - The map of the parsed text to the markdown input.
+/// * `ctx` - The state of the document which is being built.
+///
+/// # Errors
+/// - Returns an `ErrorParse::ParsingError` if there's an error during a parsing process.
+///
+/// # Returns
+/// A result containing the inline elements of the heading.
+fn parse_setext_heading(
+    pair: Pair<Rule>,
+    map: &SourceMap,
+    ctx: &mut BuildContext,
+) -> Result<Vec<Inline>, ErrorParse> {
+    let setext_text = pair.into_inner().next().ok_or_else(|| {
+        ErrorParse::ParsingError(String::from(
+            "Expected setext_text rule inside a setext heading",
+        ))
+    })?;
+
+    let mut children = Vec::new();
+    let mut previous_end = None;
+
+    for line in setext_text.into_inner() {
+        if let Some(previous_end) = previous_end {
+            let separator = Inline {
+                kind: InlineKind::Text(String::from(" ")),
+                span: ctx.span(map, previous_end, line.as_span().start()),
+            };
+            push_inline(&mut children, separator);
+        }
+        previous_end = Some(line_end(&line));
+
+        for inline in parse_inlines(line.into_inner(), map, ctx)? {
+            push_inline(&mut children, inline);
+        }
+    }
+
+    Ok(children)
+}
+
+/// Parses the lines of a disabled block as lines of a paragraph, so the block is kept as literal text.
+/// Styled text inside the lines is still parsed, empty lines are kept as line breaks.
+///
+/// # Arguments
+/// * `pair` - A `pest::iterators::Pair` representing the disabled block.
+/// * `map` - The map of the parsed text to the markdown input.
+/// * `ctx` - The state of the document which is being built.
+///
+/// # Errors
+/// - Returns an `ErrorParse::ParsingError` if there's an error during a parsing process.
+///
+/// # Returns
+/// A result containing the inline elements of the paragraph.
+fn parse_literal_block(
+    pair: Pair<Rule>,
+    map: &SourceMap,
+    ctx: &mut BuildContext,
+) -> Result<Vec<Inline>, ErrorParse> {
+    let mut children = Vec::new();
+    let mut previous_end = None;
+    let mut line_start = pair.as_span().start();
+
+    for line in pair.as_str().trim_end_matches(['\r', '\n']).split('\n') {
+        let text = line.trim_end_matches('\r');
+
+        if let Some(previous_end) = previous_end {
+            children.push(Inline {
+                kind: InlineKind::LineBreak,
+                span: ctx.span(map, previous_end, line_start),
+            });
+        }
+        previous_end = Some(line_start + text.len());
+
+        if !text.is_empty() {
+            let line_map = map.shifted(line_start);
+            let paragraph_line = parse_content(Rule::paragraph_line, text, &line_map, ctx)?
+                .next()
+                .ok_or_else(|| {
+                    ErrorParse::ParsingError(String::from(
+                        "Expected paragraph_line inside a disabled block",
+                    ))
+                })?;
+
+            for inline in parse_inlines(paragraph_line.into_inner(), &line_map, ctx)? {
+                push_inline(&mut children, inline);
+            }
+        }
+
+        line_start += line.len() + 1;
+    }
+
+    Ok(children)
+}
+
+/// Returns the offset of the end of a line of text, before its line break.
+///
+/// # Arguments
+/// * `line` - A `pest::iterators::Pair` representing the line.
+///
+/// # Returns
+/// The offset of the end of the line.
+fn line_end(line: &Pair<Rule>) -> usize {
+    line.as_span().start() + line.as_str().trim_end_matches(['\r', '\n']).len()
+}
+
+/// Parses a code block from the markdown input.
+/// Code block always has a content and may have a language of the code.
+///
+/// # Arguments
+/// * `pair` - A `pest::iterators::Pair` representing the code block rule.
+///
+/// # Errors
+/// - Returns an `ErrorParse::ParsingError` if there's an error during a parsing process.
+///
+/// # Returns
+/// A result containing a `BlockKind::CodeBlock`.
+fn parse_code_block(pair: Pair<Rule>) -> Result<BlockKind, ErrorParse> {
+    let mut lang = None;
+    let mut content = None;
+
+    for inner in pair.into_inner() {
+        match inner.as_rule() {
+            Rule::code_lang => lang = Some(String::from(inner.as_str().trim())),
+            Rule::code_content => content = Some(inner.as_str()),
+            _ => {}
+        }
+    }
+
+    let content = content.ok_or_else(|| {
+        ErrorParse::ParsingError(String::from("Expected code content inside a code block"))
+    })?;
+    // Without a language, the line break after the opening backticks is a part of the content.
+    let content = match lang {
+        Some(_) => content,
+        None => content
+            .strip_prefix("\r\n")
+            .or_else(|| content.strip_prefix('\n'))
+            .unwrap_or(content),
+    };
+
+    Ok(BlockKind::CodeBlock {
+        lang,
+        content: String::from(content),
+    })
+}
+
+/// Parses a blockquote (e.g., `> quote text`) from the markdown input.
+/// The content of the quote is parsed as a nested markdown document.
+///
+/// # Arguments
+/// * `pair` - A `pest::iterators::Pair` representing the blockquote rule.
+/// * `map` - The map of the parsed text to the markdown input.
+/// * `ctx` - The state of the document which is being built.
+///
+/// # Errors
+/// - Returns an `ErrorParse::ParsingError` if there's an error during a parsing process.
+///
+/// # Returns
+/// A result containing the blocks of the blockquote.
+fn parse_quote(
+    pair: Pair<Rule>,
+    map: &SourceMap,
+    ctx: &mut BuildContext,
+) -> Result<Vec<Block>, ErrorParse> {
+    let quote_map = quote_source_map(&pair, map);
+    let content = quote_content(pair);
+    let markdown_content = parse_content(Rule::markdown, &content, &quote_map, ctx)?
+        .next()
+        .ok_or_else(|| {
+            ErrorParse::ParsingError(String::from("Expected markdown inside a quote"))
+        })?;
+
+    parse_blocks(markdown_content.into_inner(), &quote_map, ctx)
+}
+
+/// Builds the content of a blockquote, removing the `>` markers from all of its lines.
+/// Every line of the content corresponds to the line of the quote in the markdown input.
+///
+/// # Arguments
+/// * `pair` - A `pest::iterators::Pair` representing the blockquote rule.
+///
+/// # Returns
+/// The markdown content of the blockquote.
+pub(crate) fn quote_content(pair: Pair<Rule>) -> String {
+    let lines: Vec<&str> = pair
+        .into_inner()
+        .map(|line| {
+            line.into_inner()
+                .next()
+                .map(|text| text.as_str())
+                .unwrap_or_default()
+        })
+        .collect();
+
+    lines.join("\n")
+}
+
+/// Parses a part of the markdown input which is parsed separately, e.g. the content of a blockquote, with the `rule`.
+/// Syntax errors are located in the markdown input instead of the content.
+///
+/// # Arguments
+/// * `rule` - The rule to parse the content with.
+/// * `content` - The parsed content.
+/// * `map` - The map of the content to the markdown input.
+/// * `ctx` - The state of the document which is being built.
+///
+/// # Errors
+/// - Returns an `ErrorParse::SyntaxError` if the content doesn't match the grammar.
+///
+/// # Returns
+/// A result containing the parsed `pest::iterators::Pairs` of the content.
+fn parse_content<'c>(
+    rule: Rule,
+    content: &'c str,
+    map: &SourceMap,
+    ctx: &BuildContext,
+) -> Result<Pairs<'c, Rule>, ErrorParse> {
+    parse_by_rule(rule, content).map_err(|err| match err {
+        ErrorParse::SyntaxError(err) => ErrorParse::SyntaxError(SyntaxError::new(
+            ctx.source,
+            map.offset(err.offset),
+            err.expected,
+        )),
+        err => err,
+    })
+}
+
+/// Builds the map of the content of a blockquote (see `quote_content`) to the markdown input.
+///
+/// # Arguments
+/// * `pair` - A `pest::iterators::Pair` representing the blockquote rule.
+/// * `map` - The map of the text containing the blockquote to the markdown input.
+///
+/// # Returns
+/// The map of the blockquote content.
+fn quote_source_map(pair: &Pair<Rule>, map: &SourceMap) -> SourceMap {
+    let mut segments = Vec::new();
+    let mut content_start = 0;
+
+    for line in pair.clone().into_inner() {
+        let (start, length) = match line.clone().into_inner().next() {
+            Some(text) => (text.as_span().start(), text.as_str().len()),
+            None => (line_end(&line), 0),
+        };
+        segments.push((content_start, map.offset(start)));
+        content_start += length + 1;
+    }
+
+    SourceMap { segments }
+}
+
+/// Parses an ordered or unordered list with its items from the markdown input.
+///
+/// # Arguments
+/// * `pair` - A `pest::iterators::Pair` representing the list rule.
+/// * `map` - The map of the parsed text to the markdown input.
+/// * `ctx` - The state of the document which is being built.
+///
+/// # Errors
+/// - Returns an `ErrorParse::ParsingError` if there's an error during a parsing process.
+///
+/// # Returns
+/// A result containing the list.
+fn parse_list(
+    pair: Pair<Rule>,
+    map: &SourceMap,
+    ctx: &mut BuildContext,
+) -> Result<List, ErrorParse> {
+    let span = ctx.pair_span(map, &pair);
+    let ordered = pair.as_rule() == Rule::ordered_list;
+    let start = match ordered {
+        true => parse_ordered_list_start(pair.clone())?,
+        false => 1,
+    };
+    let items = pair
+        .into_inner()
+        .map(|item| parse_list_item(item, map, ctx))
+        .collect::<Result<Vec<ListItem>, ErrorParse>>()?;
+
+    Ok(List {
+        ordered,
+        start,
+        items,
+        span,
+    })
+}
+
+/// Parses the start number of an ordered list (e.g., `3` for `3. item`).
+///
+/// # Arguments
+/// * `pair` - A `pest::iterators::Pair` representing the ordered list rule.
+///
+/// # Errors
+/// - Returns an `ErrorParse::ParsingError` if the list has no items or the number can't be read.
+///
+/// # Returns
+/// A result containing the number of the first list item.
+fn parse_ordered_list_start(pair: Pair<Rule>) -> Result<u64, ErrorParse> {
+    let number = pair
+        .into_inner()
+        .flatten()
+        .find(|inner| inner.as_rule() == Rule::list_number)
+        .ok_or_else(|| {
+            ErrorParse::ParsingError(String::from("Expected list_number inside an ordered_list"))
+        })?;
+
+    number
+        .as_str()
+        .parse()
+        .map_err(|_| ErrorParse::ParsingError(format!("Invalid list number: {}", number.as_str())))
+}
+
+/// Parses a single list item (e.g., `- item` or `1. item`) with its nested lists and blocks.
+/// The lines of the item text are separated by `InlineKind::LineBreak`, like the lines of a paragraph.
+/// If task lists are disabled, the checkbox of the item is kept as literal text.
+///
+/// # Arguments
+/// * `pair` - A `pest::iterators::Pair` representing the list item rule.
+/// * `map` - The map of the parsed text to the markdown input.
+/// * `ctx` - The state of the document which is being built.
+///
+/// # Errors
+/// - Returns an `ErrorParse::ParsingError` if there's an error during a parsing process.
+///
+/// # Returns
+/// A result containing the list item.
+fn parse_list_item(
+    pair: Pair<Rule>,
+    map: &SourceMap,
+    ctx: &mut BuildContext,
+) -> Result<ListItem, ErrorParse> {
+    let mut item = ListItem {
+        checked: None,
+        children: Vec::new(),
+        nested: None,
+        blocks: Vec::new(),
+        span: ctx.pair_span(map, &pair),
+    };
+
+    let item_start = pair.as_span().start();
+    let source = pair.as_str();
+    let mut literal_checkbox = None;
+
+    for inner in pair.into_inner() {
+        match inner.as_rule() {
+            Rule::task_checkbox if !ctx.options.allows(Rule::task_checkbox) => {
+                literal_checkbox = Some(inner.as_span().start())
+            }
+            Rule::task_checkbox => item.checked = Some(inner.into_inner().next().is_some()),
+            Rule::list_item_text => {
+                let text_start = inner.as_span().start();
+                if let Some(checkbox_start) = literal_checkbox {
+                    item.children.push(Inline {
+                        kind: InlineKind::Text(String::from(
+                            &source[checkbox_start - item_start..text_start - item_start],
+                        )),
+                        span: ctx.span(map, checkbox_start, text_start),
+                    });
+                }
+                for inline in parse_paragraph(inner, map, ctx)? {
+                    push_inline(&mut item.children, inline);
+                }
+            }
+            Rule::unordered_list | Rule::ordered_list => {
+                let list = parse_list(inner, map, ctx)?;
+                match item.nested {
+                    Some(_) => item.blocks.push(Block {
+                        span: list.span,
+                        kind: BlockKind::List(list),
+                    }),
+                    None => item.nested = Some(list),
+                }
+            }
+            Rule::list_item_content => {
+                let blocks = parse_list_item_content(inner, map, ctx)?;
+                item.blocks.extend(blocks);
+            }
+            _ => {}
+        }
+    }
+
+    Ok(item)
+}
+
+/// Parses the blocks of a list item after its text (e.g., an indented paragraph after an empty line).
+/// The content of the item is parsed as a nested markdown document.
+///
+/// # Arguments
+/// * `pair` - A `pest::iterators::Pair` representing the list item content rule.
+/// * `map` - The map of the parsed text to the markdown input.
+/// * `ctx` - The state of the document which is being built.
+///
+/// # Errors
+/// - Returns an `ErrorParse::ParsingError` if there's an error during a parsing process.
+///
+/// # Returns
+/// A result containing the blocks of the list item.
+fn parse_list_item_content(
+    pair: Pair<Rule>,
+    map: &SourceMap,
+    ctx: &mut BuildContext,
+) -> Result<Vec<Block>, ErrorParse> {
+    let (content, content_map) = list_item_content(pair, map);
+    let markdown_content = parse_content(Rule::markdown, &content, &content_map, ctx)?
+        .next()
+        .ok_or_else(|| {
+            ErrorParse::ParsingError(String::from("Expected markdown inside a list item"))
+        })?;
+
+    parse_blocks(markdown_content.into_inner(), &content_map, ctx)
+}
+
+/// Builds the content of a list item after its text, with the map of the content to the markdown input.
+/// Empty lines before the first block are skipped, and the common indentation is removed from all lines.
+///
+/// # Arguments
+/// * `pair` - A `pest::iterators::Pair` representing the list item content rule.
+/// * `map` - The map of the text containing the list item to the markdown input.
+///
+/// # Returns
+/// A tuple of the markdown content of the list item and its map.
+fn list_item_content(pair: Pair<Rule>, map: &SourceMap) -> (String, SourceMap) {
+    let lines: Vec<Pair<Rule>> = pair
+        .into_inner()
+        .skip_while(|line| line.as_rule() == Rule::list_item_blank_line)
+        .collect();
+    let texts: Vec<Option<Pair<Rule>>> = lines
+        .iter()
+        .map(|line| line.clone().into_inner().next())
+        .collect();
+    let indent = texts
+        .iter()
+        .flatten()
+        .map(|text| text.as_str().len() - text.as_str().trim_start_matches([' ', '\t']).len())
+        .min()
+        .unwrap_or_default();
+
+    let mut content = Vec::new();
+    let mut segments = Vec::new();
+    let mut content_start = 0;
+
+    for (line, text) in lines.iter().zip(texts) {
+        let (start, text) = match text {
+            Some(text) => (text.as_span().start() + indent, &text.as_str()[indent..]),
+            None => (line.as_span().start(), ""),
+        };
+        segments.push((content_start, map.offset(start)));
+        content_start += text.len() + 1;
+        content.push(text);
+    }
+
+    (content.join("\n"), SourceMap { segments })
+}
+
+/// Parses a table with its header, column alignments and body rows from the markdown input.
+/// Body rows are normalized to the number of header cells: missing cells are left empty, extra cells are dropped.
+///
+/// # Arguments
+/// * `pair` - A `pest::iterators::Pair` representing the table rule.
+/// * `map` - The map of the parsed text to the markdown input.
+/// * `ctx` - The state of the document which is being built.
+///
+/// # Errors
+/// - Returns an `ErrorParse::ParsingError` if there's an error during a parsing process.
+///
+/// # Returns
+/// A result containing a `BlockKind::Table`.
+fn parse_table(
+    pair: Pair<Rule>,
+    map: &SourceMap,
+    ctx: &mut BuildContext,
+) -> Result<BlockKind, ErrorParse> {
+    let mut inner = pair.into_inner();
+    let header = inner.next().ok_or_else(|| {
+        ErrorParse::ParsingError(String::from("Expected table_row rule inside a table"))
+    })?;
+    let delimiter = inner.next().ok_or_else(|| {
+        ErrorParse::ParsingError(String::from(
+            "Expected table_delimiter_row rule inside a table",
+        ))
+    })?;
+
+    let header = header
+        .into_inner()
+        .map(|cell| parse_table_cell(cell, map, ctx))
+        .collect::<Result<Vec<TableCell>, ErrorParse>>()?;
+    let alignments = delimiter
+        .into_inner()
+        .map(|alignment| {
+            let alignment = alignment.as_str().trim();
+            match (alignment.starts_with(':'), alignment.ends_with(':')) {
+                (true, true) => Alignment::Center,
+                (true, false) => Alignment::Left,
+                (false, true) => Alignment::Right,
+                (false, false) => Alignment::None,
+            }
+        })
+        .collect();
+
+    let mut rows = Vec::new();
+    for row in inner {
+        let row_end = line_end(&row);
+        let mut cells = row.into_inner();
+        let mut row_cells = Vec::new();
+        for _ in 0..header.len() {
+            row_cells.push(match cells.next() {
+                Some(cell) => parse_table_cell(cell, map, ctx)?,
+                None => TableCell {
+                    children: Vec::new(),
+                    span: ctx.span(map, row_end, row_end),
+                },
+            });
+        }
+        rows.push(row_cells);
+    }
+
+    Ok(BlockKind::Table {
+        alignments,
+        header,
+        rows,
+    })
+}
+
+/// Parses a single table cell with its styled text.
+///
+/// # Arguments
+/// * `pair` - A `pest::iterators::Pair` representing the table cell rule.
+/// * `map` - The map of the parsed text to the markdown input.
+/// * `ctx` - The state of the document which is being built.
+///
+/// # Errors
+/// - Returns an `ErrorParse::ParsingError` if there's an error during a parsing process.
+///
+/// # Returns
+/// A result containing the table cell.
+fn parse_table_cell(
+    pair: Pair<Rule>,
+    map: &SourceMap,
+    ctx: &mut BuildContext,
+) -> Result<TableCell, ErrorParse> {
+    Ok(TableCell {
+        span: ctx.pair_span(map, &pair),
+        children: parse_inlines(pair.into_inner(), map, ctx)?,
+    })
+}
+
+/// Parses a link reference definition (e.g., `[label]: url "title"`).
+///
+/// # Arguments
+/// * `pair` - A `pest::iterators::Pair` representing the link definition rule.
+///
+/// # Errors
+/// - Returns an `ErrorParse::ParsingError` if there's an error during a parsing process.
+///
+/// # Returns
+/// A result containing a tuple of the label, the destination and the optional title.
+fn parse_link_definition(pair: Pair<Rule>) -> Result<(String, String, Option<String>), ErrorParse> {
+    let mut label = None;
+    let mut destination = None;
+    let mut title = None;
+
+    for inner in pair.into_inner() {
+        match inner.as_rule() {
+            Rule::link_label => label = Some(String::from(inner.as_str())),
+            Rule::link_destination => {
+                destination = Some(String::from(parse_link_destination(&inner)))
+            }
+            Rule::link_title => title = Some(parse_link_title(inner)),
+            _ => {}
+        }
+    }
+
+    let label = label.ok_or_else(|| {
+        ErrorParse::ParsingError(String::from(
+            "Expected link_label rule inside a link_definition",
+        ))
+    })?;
+    let destination = destination.ok_or_else(|| {
+        ErrorParse::ParsingError(String::from(
+            "Expected link_destination rule inside a link_definition",
+        ))
+    })?;
+
+    Ok((label, destination, title))
+}
+
+/// Reads the destination of a link or an image, removing the enclosing `<` and `>` if they are present.
+///
+/// # Arguments
+/// * `pair` - A `pest::iterators::Pair` representing the url or link destination rule.
+///
+/// # Returns
+/// The destination as written in the markdown input.
+fn parse_link_destination<'i>(pair: &Pair<'i, Rule>) -> &'i str {
+    let destination = pair.as_str();
+    match destination.strip_prefix('<') {
+        Some(enclosed) => enclosed.strip_suffix('>').unwrap_or(enclosed),
+        None => destination,
+    }
+}
+
+/// Reads the text of a link title, removing the enclosing quotes or parentheses.
+///
+/// # Arguments
+/// * `pair` - A `pest::iterators::Pair` representing the link title rule.
+///
+/// # Returns
+/// The text of the title.
+fn parse_link_title(pair: Pair<Rule>) -> String {
+    let title = pair.as_str();
+    String::from(&title[1..title.len() - 1])
+}
+
+/// Reads the label of a footnote definition (e.g., `note` for `[^note]: text`).
+///
+/// # Arguments
+/// * `pair` - A `pest::iterators::Pair` representing the footnote definition rule.
+///
+/// # Returns
+/// The label as written in the markdown input.
+fn footnote_definition_label<'i>(pair: &Pair<'i, Rule>) -> &'i str {
+    pair.clone()
+        .into_inner()
+        .next()
+        .map(|label| label.as_str())
+        .unwrap_or_default()
+}
+
+/// Normalizes a footnote or link label, so labels which differ only in letter case or whitespace match each other.
+///
+/// # Arguments
+/// * `label` - The label as written in the markdown input.
+///
+/// # Returns
+/// The normalized label.
+pub(crate) fn normalize_label(label: &str) -> String {
+    label
+        .split_whitespace()
+        .collect::<Vec<&str>>()
+        .join(" ")
+        .to_lowercase()
+}
+
+/// Collects footnote definitions of the document tree by their normalized label, including the ones inside blockquotes and list items.
+/// If several definitions have the same label, the first one is used.
+///
+/// # Arguments
+/// * `blocks` - Blocks of the document.
+/// * `footnotes` - The map to which found definitions are added.
+pub(crate) fn collect_footnotes<'a>(
+    blocks: &'a [Block],
+    footnotes: &mut HashMap<String, &'a [Inline]>,
+) {
+    for block in blocks {
+        match &block.kind {
+            BlockKind::FootnoteDefinition { label, children } => {
+                footnotes
+                    .entry(normalize_label(label))
+                    .or_insert(children.as_slice());
+            }
+            BlockKind::Quote { children } => collect_footnotes(children, footnotes),
+            BlockKind::List(list) => collect_list_footnotes(list, footnotes),
+            _ => {}
+        }
+    }
+}
+
+/// Collects footnote definitions from the blocks of the items of a `list` and its nested lists.
+///
+/// # Arguments
+/// * `list` - The list.
+/// * `footnotes` - Texts of the footnotes by their normalized labels.
+fn collect_list_footnotes<'a>(list: &'a List, footnotes: &mut HashMap<String, &'a [Inline]>) {
+    for item in &list.items {
+        if let Some(nested) = &item.nested {
+            collect_list_footnotes(nested, footnotes);
+        }
+        collect_footnotes(&item.blocks, footnotes);
+    }
+}
+
+/// Returns normalized labels of the referenced footnotes in the order of their first reference.
+/// Footnotes referenced only from the text of other referenced footnotes are included after them.
+///
+/// # Arguments
+/// * `document` - The document tree.
+///
+/// # Returns
+/// Labels of the referenced footnotes.
+fn referenced_footnotes(document: &Document) -> Vec<String> {
+    let mut footnotes = HashMap::new();
+    collect_footnotes(&document.blocks, &mut footnotes);
+
+    let mut order = Vec::new();
+    collect_block_references(&document.blocks, &mut order);
+
+    let mut index = 0;
+    while index < order.len() {
+        if let Some(children) = footnotes.get(&order[index]) {
+            collect_inline_references(children, &mut order);
+        }
+        index += 1;
+    }
+
+    order
+}
+
+/// Collects normalized labels of footnote references inside `blocks`, skipping the ones already collected.
+///
+/// # Arguments
+/// * `blocks` - Blocks of the document.
+/// * `order` - Labels of the referenced footnotes in the order of their first reference.
+fn collect_block_references(blocks: &[Block], order: &mut Vec<String>) {
+    for block in blocks {
+        match &block.kind {
+            BlockKind::Heading { children, .. } | BlockKind::Paragraph { children } => {
+                collect_inline_references(children, order)
+            }
+            BlockKind::Quote { children } => collect_block_references(children, order),
+            BlockKind::List(list) => collect_list_references(list, order),
+            BlockKind::Table { header, rows, .. } => {
+                for cell in header.iter().chain(rows.iter().flatten()) {
+                    collect_inline_references(&cell.children, order);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Collects normalized labels of footnote references inside the items of a `list`, its nested lists and blocks.
+///
+/// # Arguments
+/// * `list` - The list.
+/// * `order` - Labels of the referenced footnotes in the order of their first reference.
+fn collect_list_references(list: &List, order: &mut Vec<String>) {
+    for item in &list.items {
+        collect_inline_references(&item.children, order);
+        if let Some(nested) = &item.nested {
+            collect_list_references(nested, order);
+        }
+        collect_block_references(&item.blocks, order);
+    }
+}
+
+/// Collects normalized labels of footnote references inside `inlines`, skipping the ones already collected.
+///
+/// # Arguments
+/// * `inlines` - Inline elements.
+/// * `order` - Labels of the referenced footnotes in the order of their first reference.
+fn collect_inline_references(inlines: &[Inline], order: &mut Vec<String>) {
+    for inline in inlines {
+        match &inline.kind {
+            InlineKind::FootnoteReference { label } => {
+                let label = normalize_label(label);
+                if !order.contains(&label) {
+                    order.push(label);
+                }
+            }
+            InlineKind::Strong(children)
+            | InlineKind::Emphasis(children)
+            | InlineKind::Strikethrough(children)
+            | InlineKind::Underline(children)
+            | InlineKind::Link { children, .. } => collect_inline_references(children, order),
+            _ => {}
+        }
+    }
+}
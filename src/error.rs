@@ -0,0 +1,171 @@
+use std::fmt;
+
+use pest::error::{Error as PestError, ErrorVariant, InputLocation};
+
+use crate::Rule;
+
+/// A syntax error in a markdown input: the location where the input stopped matching the grammar
+/// and the markdown constructs which could have been there.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SyntaxError {
+    /// The line number of the error, starting from 1.
+    pub line: usize,
+    /// The column number of the error in characters, starting from 1.
+    pub column: usize,
+    /// The byte offset of the error from the start of the input.
+    pub offset: usize,
+    /// The line of the input on which the error is located, without the line break.
+    pub snippet: String,
+    /// Human-readable names of the markdown constructs which were expected at the location of the error.
+    pub expected: Vec<String>,
+}
+
+impl SyntaxError {
+    /// Creates an error located at the byte `offset` of the `input`.
+    ///
+    /// # Arguments
+    /// * `input` - The input in which the error is located.
+    /// * `offset` - The byte offset of the error, it's moved to the closest character boundary before it.
+    /// * `expected` - Names of the expected markdown constructs.
+    ///
+    /// # Returns
+    /// The syntax error.
+    pub(crate) fn new(input: &str, offset: usize, expected: Vec<String>) -> Self {
+        let mut offset = offset.min(input.len());
+        while !input.is_char_boundary(offset) {
+            offset -= 1;
+        }
+
+        let line_start = input[..offset].rfind('\n').map_or(0, |i| i + 1);
+        let line_end = input[offset..]
+            .find('\n')
+            .map_or(input.len(), |i| offset + i);
+
+        SyntaxError {
+            line: input[..line_start].matches('\n').count() + 1,
+            column: input[line_start..offset].chars().count() + 1,
+            offset,
+            snippet: String::from(input[line_start..line_end].trim_end_matches('\r')),
+            expected,
+        }
+    }
+
+    /// Creates an error from an error of the `pest` parser, naming the expected rules as markdown constructs.
+    ///
+    /// # Arguments
+    /// * `input` - The input which was parsed.
+    /// * `error` - The error returned by the parser.
+    ///
+    /// # Returns
+    /// The syntax error.
+    pub(crate) fn from_pest(input: &str, error: PestError<Rule>) -> Self {
+        let offset = match error.location {
+            InputLocation::Pos(offset) => offset,
+            InputLocation::Span((start, _)) => start,
+        };
+
+        let mut expected: Vec<String> = Vec::new();
+        if let ErrorVariant::ParsingError { positives, .. } = &error.variant {
+            for rule in positives {
+                let name = describe_rule(*rule);
+                if !expected.iter().any(|known| known == name) {
+                    expected.push(String::from(name));
+                }
+            }
+        }
+
+        SyntaxError::new(input, offset, expected)
+    }
+}
+
+impl fmt::Display for SyntaxError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.expected.split_last() {
+            None => write!(f, "unexpected input")?,
+            Some((last, [])) => write!(f, "expected {}", last)?,
+            Some((last, rest)) => write!(f, "expected {} or {}", rest.join(", "), last)?,
+        }
+        writeln!(f, " at line {}, column {}", self.line, self.column)?;
+
+        // Tabs are kept, so the caret is aligned with the snippet.
+        let caret_indent: String = self
+            .snippet
+            .chars()
+            .take(self.column - 1)
+            .map(|c| if c == '\t' { '\t' } else { ' ' })
+            .collect();
+        let gutter = " ".repeat(self.line.to_string().len());
+
+        writeln!(f, "{} |", gutter)?;
+        writeln!(f, "{} | {}", self.line, self.snippet)?;
+        write!(f, "{} | {}^", gutter, caret_indent)
+    }
+}
+
+impl std::error::Error for SyntaxError {}
+
+/// Returns the human-readable name of the markdown construct described by a grammar rule.
+///
+/// # Arguments
+/// * `rule` - The grammar rule.
+///
+/// # Returns
+/// The name of the construct.
+fn describe_rule(rule: Rule) -> &'static str {
+    match rule {
+        Rule::markdown => "markdown document",
+        Rule::EOI => "end of input",
+        Rule::empty_line => "empty line",
+        Rule::heading1
+        | Rule::heading2
+        | Rule::heading3
+        | Rule::heading4
+        | Rule::heading5
+        | Rule::heading6
+        | Rule::setext_heading1
+        | Rule::setext_heading2
+        | Rule::setext_text => "heading",
+        Rule::single_line_text | Rule::heading_text => "heading text",
+        Rule::horizontal_rule => "horizontal rule",
+        Rule::code_block | Rule::code_content => "code block",
+        Rule::code_lang => "code block language",
+        Rule::unordered_list | Rule::ordered_list => "list",
+        Rule::unordered_item | Rule::ordered_item => "list item",
+        Rule::bullet_marker | Rule::ordered_marker | Rule::list_number => "list marker",
+        Rule::list_item_text => "list item text",
+        Rule::list_item_content
+        | Rule::list_item_blank_line
+        | Rule::list_item_content_line
+        | Rule::list_item_content_text => "list item content",
+        Rule::task_checkbox | Rule::task_checked => "task checkbox",
+        Rule::table | Rule::table_row => "table row",
+        Rule::table_delimiter_row | Rule::table_alignment => "table delimiter row",
+        Rule::table_cell | Rule::table_text => "table cell",
+        Rule::footnote_definition | Rule::footnote_text => "footnote definition",
+        Rule::footnote_reference => "footnote reference",
+        Rule::footnote_label => "footnote label",
+        Rule::link_definition => "link definition",
+        Rule::link_destination | Rule::url => "link destination",
+        Rule::link_title => "link title",
+        Rule::link_label => "link label",
+        Rule::quote
+        | Rule::quote_blank_line
+        | Rule::quote_line
+        | Rule::quote_lazy_line
+        | Rule::quote_line_text => "blockquote",
+        Rule::paragraph | Rule::paragraph_line => "paragraph",
+        Rule::code_span | Rule::code_span_content => "code span",
+        Rule::inline_link | Rule::reference_link => "link",
+        Rule::inline_image | Rule::reference_image => "image",
+        Rule::link_text | Rule::reference_text | Rule::link_plain_text | Rule::link_line_break => {
+            "link text"
+        }
+        Rule::alt_text => "image description",
+        Rule::strikethrough => "strikethrough text",
+        Rule::underline => "underlined text",
+        Rule::bold => "bold text",
+        Rule::italic => "italic text",
+        Rule::escaped | Rule::char => "escaped character",
+        _ => "text",
+    }
+}
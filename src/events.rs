@@ -0,0 +1,248 @@
+use crate::ast::{Alignment, Block, BlockKind, Document, Inline, InlineKind, ListItem, TableCell};
+
+/// A markdown element which contains other events, opened by `Event::Start` and closed by `Event::End`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Tag {
+    /// A heading with its level (1-6).
+    Heading(u8),
+    /// A paragraph.
+    Paragraph,
+    /// A fenced code block with its optional language, its content is a single `Event::Text`.
+    CodeBlock(Option<String>),
+    /// A blockquote.
+    BlockQuote,
+    /// A list: `None` for an unordered list, or the number of the first item of an ordered list.
+    List(Option<u64>),
+    /// A list item, with the state of its checkbox if it's a task item. Nested lists and blocks of the item are a part of it.
+    Item(Option<bool>),
+    /// A table with the alignments of its columns.
+    Table(Vec<Alignment>),
+    /// The head of a table, which contains the header row.
+    TableHead,
+    /// The body of a table, which contains body rows. It's present only if the table has body rows.
+    TableBody,
+    /// A row of a table.
+    TableRow,
+    /// A cell of a table: whether it's a header cell, and the alignment of its column.
+    TableCell { header: bool, alignment: Alignment },
+    /// Italic text.
+    Emphasis,
+    /// Bold text.
+    Strong,
+    /// Strikethrough text.
+    Strikethrough,
+    /// Underlined text.
+    Underline,
+    /// A link with its destination and optional title.
+    Link { url: String, title: Option<String> },
+    /// An image with its source and optional title, its alternative text is a single `Event::Text`.
+    Image { url: String, title: Option<String> },
+    /// A footnote definition with its label.
+    FootnoteDefinition(String),
+}
+
+/// An event of a markdown document, produced by [`Events`] in the order of the document.
+///
+/// Plain newlines between lines of a paragraph are `SoftBreak` events, the same as in `pulldown-cmark`,
+/// but they are still rendered as `<br>`. The parser has no syntax for explicit breaks,
+/// `HardBreak` is for breaks inserted by a transformation of the events.
+/// Empty lines between blocks aren't breaks of text, they have their own `EmptyLine` event,
+/// so they can be dropped with a filter.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Event {
+    /// The start of an element.
+    Start(Tag),
+    /// The end of an element, with the same tag as its start.
+    End(Tag),
+    /// Literal text, with escaped characters already unescaped.
+    Text(String),
+    /// An inline code span with its literal content.
+    Code(String),
+    /// A plain newline between lines of a paragraph, rendered as `<br>`.
+    SoftBreak,
+    /// An explicit line break, rendered as `<br>`.
+    HardBreak,
+    /// An empty line between blocks, rendered as `<br/>`.
+    EmptyLine,
+    /// A horizontal rule.
+    Rule,
+    /// A reference to a defined footnote, with its label.
+    FootnoteReference(String),
+}
+
+/// A part of the document which hasn't been turned into events yet.
+enum Pending {
+    Event(Event),
+    Block(Block),
+    Inline(Inline),
+    Item(ListItem),
+    Row(Vec<TableCell>, bool, Vec<Alignment>),
+    Cell(TableCell, bool, Alignment),
+}
+
+/// A pull parser over a markdown document, an iterator of its [`Event`]s.
+/// Nodes of the document tree are turned into events only when the iterator reaches them,
+/// so the events can be filtered and transformed with iterator adapters.
+///
+/// Link reference definitions don't produce events, reference links and images are already resolved.
+pub struct Events {
+    /// Nodes and events which are left, the next one is at the end.
+    stack: Vec<Pending>,
+}
+
+impl Events {
+    /// Creates an iterator of the events of the `document`.
+    pub fn new(document: Document) -> Self {
+        Events {
+            stack: document
+                .blocks
+                .into_iter()
+                .rev()
+                .map(Pending::Block)
+                .collect(),
+        }
+    }
+
+    /// Opens the element with the `tag`: its children and its end are produced after the start.
+    ///
+    /// # Arguments
+    /// * `tag` - The tag of the element.
+    /// * `children` - The children of the element in the order of the document.
+    ///
+    /// # Returns
+    /// The start event of the element.
+    fn open(&mut self, tag: Tag, children: impl DoubleEndedIterator<Item = Pending>) -> Event {
+        self.stack.push(Pending::Event(Event::End(tag.clone())));
+        self.stack.extend(children.rev());
+        Event::Start(tag)
+    }
+
+    /// Turns a block into its first event.
+    ///
+    /// # Arguments
+    /// * `block` - The block.
+    ///
+    /// # Returns
+    /// The first event of the block, or `None` if the block doesn't produce events.
+    fn block(&mut self, block: Block) -> Option<Event> {
+        let inlines = |children: Vec<Inline>| children.into_iter().map(Pending::Inline);
+
+        let event = match block.kind {
+            BlockKind::Heading { level, children } => {
+                self.open(Tag::Heading(level), inlines(children))
+            }
+            BlockKind::Paragraph { children } => self.open(Tag::Paragraph, inlines(children)),
+            BlockKind::CodeBlock { lang, content } => self.open(
+                Tag::CodeBlock(lang),
+                std::iter::once(Pending::Event(Event::Text(content))),
+            ),
+            BlockKind::Quote { children } => {
+                self.open(Tag::BlockQuote, children.into_iter().map(Pending::Block))
+            }
+            BlockKind::List(list) => self.open(
+                Tag::List(list.ordered.then_some(list.start)),
+                list.items.into_iter().map(Pending::Item),
+            ),
+            BlockKind::Table {
+                alignments,
+                header,
+                rows,
+            } => {
+                let mut children = vec![
+                    Pending::Event(Event::Start(Tag::TableHead)),
+                    Pending::Row(header, true, alignments.clone()),
+                    Pending::Event(Event::End(Tag::TableHead)),
+                ];
+                if !rows.is_empty() {
+                    children.push(Pending::Event(Event::Start(Tag::TableBody)));
+                    children.extend(
+                        rows.into_iter()
+                            .map(|row| Pending::Row(row, false, alignments.clone())),
+                    );
+                    children.push(Pending::Event(Event::End(Tag::TableBody)));
+                }
+                self.open(Tag::Table(alignments), children.into_iter())
+            }
+            BlockKind::HorizontalRule => Event::Rule,
+            BlockKind::EmptyLine => Event::EmptyLine,
+            BlockKind::FootnoteDefinition { label, children } => {
+                self.open(Tag::FootnoteDefinition(label), inlines(children))
+            }
+            BlockKind::LinkDefinition { .. } => return None,
+        };
+
+        Some(event)
+    }
+
+    /// Turns an inline element into its first event.
+    ///
+    /// # Arguments
+    /// * `inline` - The inline element.
+    ///
+    /// # Returns
+    /// The first event of the inline element.
+    fn inline(&mut self, inline: Inline) -> Event {
+        let inlines = |children: Vec<Inline>| children.into_iter().map(Pending::Inline);
+
+        match inline.kind {
+            InlineKind::Text(text) => Event::Text(text),
+            InlineKind::Code(code) => Event::Code(code),
+            InlineKind::Strong(children) => self.open(Tag::Strong, inlines(children)),
+            InlineKind::Emphasis(children) => self.open(Tag::Emphasis, inlines(children)),
+            InlineKind::Strikethrough(children) => self.open(Tag::Strikethrough, inlines(children)),
+            InlineKind::Underline(children) => self.open(Tag::Underline, inlines(children)),
+            InlineKind::Link {
+                url,
+                title,
+                children,
+            } => self.open(Tag::Link { url, title }, inlines(children)),
+            InlineKind::Image { url, title, alt } => self.open(
+                Tag::Image { url, title },
+                std::iter::once(Pending::Event(Event::Text(alt))),
+            ),
+            InlineKind::FootnoteReference { label } => Event::FootnoteReference(label),
+            InlineKind::LineBreak => Event::SoftBreak,
+        }
+    }
+}
+
+impl Iterator for Events {
+    type Item = Event;
+
+    fn next(&mut self) -> Option<Event> {
+        loop {
+            let event = match self.stack.pop()? {
+                Pending::Event(event) => Some(event),
+                Pending::Block(block) => self.block(block),
+                Pending::Inline(inline) => Some(self.inline(inline)),
+                Pending::Item(item) => {
+                    let mut children: Vec<Pending> =
+                        item.children.into_iter().map(Pending::Inline).collect();
+                    if let Some(nested) = item.nested {
+                        children.push(Pending::Block(Block {
+                            span: nested.span,
+                            kind: BlockKind::List(nested),
+                        }));
+                    }
+                    children.extend(item.blocks.into_iter().map(Pending::Block));
+                    Some(self.open(Tag::Item(item.checked), children.into_iter()))
+                }
+                Pending::Row(cells, header, alignments) => {
+                    let cells = cells.into_iter().enumerate().map(|(i, cell)| {
+                        let alignment = alignments.get(i).copied().unwrap_or(Alignment::None);
+                        Pending::Cell(cell, header, alignment)
+                    });
+                    Some(self.open(Tag::TableRow, cells.collect::<Vec<_>>().into_iter()))
+                }
+                Pending::Cell(cell, header, alignment) => Some(self.open(
+                    Tag::TableCell { header, alignment },
+                    cell.children.into_iter().map(Pending::Inline),
+                )),
+            };
+
+            if event.is_some() {
+                return event;
+            }
+        }
+    }
+}
@@ -0,0 +1,733 @@
+use std::collections::HashMap;
+
+use crate::{
+    ast::collect_footnotes, parse_document, Alignment, Block, BlockKind, ErrorParse, Inline,
+    InlineKind, List, ListItem, ParseWarning, Span, TableCell,
+};
+
+/// Characters which are escaped wherever they appear in text, since they can start styled text or links.
+const ESCAPED_CHARS: [char; 7] = ['\\', '`', '*', '_', '~', '[', ']'];
+
+/// Where inline elements are written, which defines the characters escaped in their text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum InlineContext {
+    /// Lines of a paragraph, which must not start another block.
+    Lines,
+    /// An ATX heading, whose text must not end with a closing sequence of `#`.
+    Heading,
+    /// A table cell, whose text must not contain a pipe.
+    Cell,
+    /// A list item or a footnote definition, whose continuation lines must not start another block.
+    Item,
+}
+
+/// Formats the markdown `text` into its canonical form:
+/// - ATX headings with a single space after the `#` sequence;
+/// - a single blank line between blocks;
+/// - `**` for bold text, `*` for italic text (or `_` if `*` would be ambiguous), `-` for unordered list items
+///   (or `*` for a list right after another one, which would be merged with it otherwise);
+/// - consecutive numbers of ordered list items and tables with aligned columns;
+/// - reference links and images resolved into inline ones;
+/// - characters escaped in the input are kept escaped, and other characters are escaped only where needed.
+///
+/// Every formatted block is parsed back and compared with the original one. A block which would change
+/// its meaning after formatting is kept as it is, together with the blank lines after it. If the formatted
+/// document still doesn't match the original one, the input is returned unchanged, so formatting never
+/// changes the meaning of a document, and formatting an already formatted text doesn't change it.
+/// Use [`format_markdown_with_warnings`] to find out which parts of the text were kept unchanged.
+///
+/// # Arguments
+/// * `text` - A string slice containing the markdown content.
+///
+/// # Errors
+/// - Returns an `ErrorParse::ParsingError` if there's an error during a parsing process.
+///
+/// # Returns
+/// A result containing either the formatted markdown or a `ErrorParse` if the parsing fails.
+pub fn format_markdown(text: &str) -> Result<String, ErrorParse> {
+    format_markdown_with_warnings(text).map(|(formatted, _)| formatted)
+}
+
+/// Formats the markdown `text` into its canonical form like [`format_markdown`], returning it with warnings
+/// about the parts of the text which couldn't be formatted without changing their meaning and were kept unchanged.
+///
+/// # Arguments
+/// * `text` - A string slice containing the markdown content.
+///
+/// # Errors
+/// - Returns an `ErrorParse::ParsingError` if there's an error during a parsing process.
+///
+/// # Returns
+/// A result containing either a tuple of the formatted markdown and `ParseWarning::Unformatted` warnings
+/// or a `ErrorParse` if the parsing fails.
+pub fn format_markdown_with_warnings(
+    text: &str,
+) -> Result<(String, Vec<ParseWarning>), ErrorParse> {
+    let document = parse_document(text)?;
+    let blocks: Vec<&Block> = content_blocks(&document.blocks).collect();
+    let expected: Vec<Block> = blocks.iter().map(|block| without_spans(block)).collect();
+
+    // Footnote references are text without a definition, so every block is checked with all definitions.
+    let mut footnotes = HashMap::new();
+    collect_footnotes(&document.blocks, &mut footnotes);
+    let definitions: String = footnotes
+        .keys()
+        .map(|label| format!("\n\n[^{}]: x", label))
+        .collect();
+
+    let bullets = list_bullets(&blocks, None);
+    let mut output = String::new();
+    let mut warnings = Vec::new();
+    for (i, block) in blocks.iter().enumerate() {
+        let formatted = format_block(block, bullets[i], text);
+        let reparsed = parse_document(&(formatted.clone() + &definitions))?;
+        let actual: Vec<Block> = content_blocks(&reparsed.blocks)
+            .map(without_spans)
+            .collect();
+
+        match actual.len() == footnotes.len() + 1 && actual.first() == expected.get(i) {
+            true => {
+                output.push_str(&formatted);
+                output.push_str(if i + 1 < blocks.len() { "\n\n" } else { "\n" });
+            }
+            // The block is kept with the source up to the next block, since the line breaks
+            // after it may belong to the block, e.g. a line break escaped with a backslash.
+            false => {
+                warnings.push(ParseWarning::Unformatted { span: block.span });
+                let end = blocks
+                    .get(i + 1)
+                    .map_or(text.len(), |next| next.span.start.offset);
+                output.push_str(text.get(block.span.start.offset..end).unwrap_or_default());
+                if i + 1 == blocks.len() && !output.ends_with('\n') {
+                    output.push('\n');
+                }
+            }
+        }
+    }
+
+    let reparsed = parse_document(&output)?;
+    let actual: Vec<Block> = content_blocks(&reparsed.blocks)
+        .map(without_spans)
+        .collect();
+    match (actual == expected, blocks.first(), blocks.last()) {
+        (false, Some(first), Some(last)) => {
+            let span = Span {
+                start: first.span.start,
+                end: last.span.end,
+            };
+            Ok((text.to_string(), vec![ParseWarning::Unformatted { span }]))
+        }
+        _ => Ok((output, warnings)),
+    }
+}
+
+/// Returns the blocks which are written by the formatter, i.e. all blocks except empty lines.
+fn content_blocks(blocks: &[Block]) -> impl Iterator<Item = &Block> {
+    blocks
+        .iter()
+        .filter(|block| block.kind != BlockKind::EmptyLine)
+}
+
+/// Chooses the bullet markers of unordered lists among `blocks`: `-`, or `*` for a list right after a list with `-`,
+/// since consecutive lists with the same marker are parsed as a single list.
+///
+/// # Arguments
+/// * `blocks` - Consecutive blocks, without empty lines.
+/// * `previous` - The bullet marker of an unordered list right before the blocks, if there is one.
+///
+/// # Returns
+/// The bullet marker of every block, which is used only if the block is an unordered list.
+fn list_bullets(blocks: &[&Block], previous: Option<char>) -> Vec<char> {
+    let mut previous = previous;
+
+    blocks
+        .iter()
+        .map(|block| {
+            let bullet = match previous {
+                Some('-') => '*',
+                _ => '-',
+            };
+            previous = match &block.kind {
+                BlockKind::List(list) if !list.ordered => Some(bullet),
+                _ => None,
+            };
+            bullet
+        })
+        .collect()
+}
+
+/// Formats consecutive blocks, separated by blank lines.
+///
+/// # Arguments
+/// * `blocks` - The blocks, empty lines among them are skipped.
+/// * `previous` - The bullet marker of an unordered list right before the blocks, if there is one.
+/// * `text` - The markdown input from which the blocks were parsed.
+///
+/// # Returns
+/// The formatted blocks without a trailing line break.
+fn format_blocks(blocks: &[Block], previous: Option<char>, text: &str) -> String {
+    let blocks: Vec<&Block> = content_blocks(blocks).collect();
+    let bullets = list_bullets(&blocks, previous);
+
+    blocks
+        .iter()
+        .zip(bullets)
+        .map(|(block, bullet)| format_block(block, bullet, text))
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+/// Returns the part of the markdown input covered by the `span`.
+fn source(text: &str, span: Span) -> &str {
+    text.get(span.start.offset..span.end.offset)
+        .unwrap_or_default()
+}
+
+/// Formats a single block.
+///
+/// # Arguments
+/// * `block` - The block.
+/// * `bullet` - The bullet marker of the items, if the block is an unordered list.
+/// * `text` - The markdown input from which the block was parsed.
+///
+/// # Returns
+/// The formatted block without a trailing line break.
+fn format_block(block: &Block, bullet: char, text: &str) -> String {
+    match &block.kind {
+        BlockKind::Heading { level, children } => {
+            let sequence = "#".repeat(usize::from(*level));
+            match children.is_empty() {
+                true => sequence,
+                false => format!(
+                    "{} {}",
+                    sequence,
+                    format_inlines(children, text, InlineContext::Heading)
+                ),
+            }
+        }
+        BlockKind::Paragraph { children } => format_inlines(children, text, InlineContext::Lines),
+        BlockKind::CodeBlock { lang, content } => format!(
+            "```{}\n{}\n```",
+            lang.as_deref().unwrap_or_default(),
+            content
+        ),
+        BlockKind::Quote { children } => {
+            let content = format_blocks(children, None, text);
+            match content.is_empty() {
+                true => String::from(">"),
+                false => content
+                    .lines()
+                    .map(|line| match line.is_empty() {
+                        true => String::from(">"),
+                        false => format!("> {}", line),
+                    })
+                    .collect::<Vec<_>>()
+                    .join("\n"),
+            }
+        }
+        BlockKind::List(list) => format_list(list, bullet, text),
+        BlockKind::Table {
+            alignments,
+            header,
+            rows,
+        } => format_table(alignments, header, rows, text),
+        BlockKind::HorizontalRule => String::from("---"),
+        BlockKind::EmptyLine => String::new(),
+        BlockKind::FootnoteDefinition { label, children } => format!(
+            "[^{}]: {}",
+            label,
+            format_inlines(children, text, InlineContext::Item)
+        ),
+        BlockKind::LinkDefinition { label, url, title } => {
+            let destination = match url.is_empty() || url.contains(char::is_whitespace) {
+                true => format!("<{}>", url),
+                false => url.clone(),
+            };
+            format!("[{}]: {}{}", label, destination, format_title(title))
+        }
+    }
+}
+
+/// Formats a list, the items of an ordered list are numbered consecutively from its start number.
+///
+/// # Arguments
+/// * `list` - The list.
+/// * `bullet` - The bullet marker of the items, if the list is unordered.
+/// * `text` - The markdown input from which the list was parsed.
+///
+/// # Returns
+/// The formatted list without a trailing line break.
+fn format_list(list: &List, bullet: char, text: &str) -> String {
+    list.items
+        .iter()
+        .enumerate()
+        .map(|(i, item)| {
+            let marker = match list.ordered {
+                true => format!("{}.", list.start + i as u64),
+                false => String::from(bullet),
+            };
+            format_item(item, &marker, text)
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Formats a list item with its nested list and blocks, which are indented by the width of the item marker
+/// like the continuation lines of the item text. The blocks are separated from the text by a blank line.
+///
+/// # Arguments
+/// * `item` - The list item.
+/// * `marker` - The marker of the item, e.g. `-` or `2.`.
+/// * `text` - The markdown input from which the item was parsed.
+///
+/// # Returns
+/// The formatted item without a trailing line break.
+fn format_item(item: &ListItem, marker: &str, text: &str) -> String {
+    let checkbox = match item.checked {
+        Some(true) => "[x] ",
+        Some(false) => "[ ] ",
+        None => "",
+    };
+    let indent = " ".repeat(marker.chars().count() + 1);
+    let mut formatted = format!(
+        "{} {}{}",
+        marker,
+        checkbox,
+        format_inlines(&item.children, text, InlineContext::Item)
+            .replace('\n', &format!("\n{}", indent))
+    );
+
+    if let Some(nested) = &item.nested {
+        for line in format_list(nested, '-', text).lines() {
+            formatted.push('\n');
+            formatted.push_str(&indent);
+            formatted.push_str(line);
+        }
+    }
+
+    if !item.blocks.is_empty() {
+        let previous = item
+            .nested
+            .as_ref()
+            .filter(|nested| !nested.ordered)
+            .map(|_| '-');
+        formatted.push('\n');
+        for line in format_blocks(&item.blocks, previous, text).lines() {
+            formatted.push('\n');
+            if !line.is_empty() {
+                formatted.push_str(&indent);
+                formatted.push_str(line);
+            }
+        }
+    }
+
+    formatted
+}
+
+/// Formats a table with columns padded to the same width.
+///
+/// # Arguments
+/// * `alignments` - Alignments of the columns.
+/// * `header` - Cells of the header row.
+/// * `rows` - Cells of the body rows.
+/// * `text` - The markdown input from which the table was parsed.
+///
+/// # Returns
+/// The formatted table without a trailing line break.
+fn format_table(
+    alignments: &[Alignment],
+    header: &[TableCell],
+    rows: &[Vec<TableCell>],
+    text: &str,
+) -> String {
+    let cells: Vec<Vec<String>> = std::iter::once(header)
+        .chain(rows.iter().map(Vec::as_slice))
+        .map(|row| {
+            row.iter()
+                .map(|cell| format_inlines(&cell.children, text, InlineContext::Cell))
+                .collect()
+        })
+        .collect();
+
+    let columns = cells
+        .iter()
+        .map(Vec::len)
+        .chain(std::iter::once(alignments.len()))
+        .max()
+        .unwrap_or_default();
+    let widths: Vec<usize> = (0..columns)
+        .map(|column| {
+            cells
+                .iter()
+                .filter_map(|row| row.get(column))
+                .map(|cell| cell.chars().count())
+                .chain(std::iter::once(3))
+                .max()
+                .unwrap_or(3)
+        })
+        .collect();
+
+    let format_row = |row: &[String]| {
+        let padded: Vec<String> = widths
+            .iter()
+            .enumerate()
+            .map(|(column, width)| {
+                let cell = row.get(column).map(String::as_str).unwrap_or_default();
+                format!("{}{}", cell, " ".repeat(width - cell.chars().count()))
+            })
+            .collect();
+        format!("| {} |", padded.join(" | "))
+    };
+    let delimiters: Vec<String> = widths
+        .iter()
+        .enumerate()
+        .map(
+            |(column, width)| match alignments.get(column).copied().unwrap_or(Alignment::None) {
+                Alignment::None => "-".repeat(*width),
+                Alignment::Left => format!(":{}", "-".repeat(width - 1)),
+                Alignment::Center => format!(":{}:", "-".repeat(width - 2)),
+                Alignment::Right => format!("{}:", "-".repeat(width - 1)),
+            },
+        )
+        .collect();
+
+    let mut lines = vec![
+        format_row(&cells[0]),
+        format!("| {} |", delimiters.join(" | ")),
+    ];
+    lines.extend(cells[1..].iter().map(|row| format_row(row)));
+    lines.join("\n")
+}
+
+/// Formats inline elements. Line breaks are written as line breaks of the markdown text.
+///
+/// # Arguments
+/// * `inlines` - The inline elements.
+/// * `text` - The markdown input from which the elements were parsed.
+/// * `context` - Where the elements are written.
+///
+/// # Returns
+/// The formatted inline elements.
+fn format_inlines(inlines: &[Inline], text: &str, context: InlineContext) -> String {
+    let mut formatted = String::new();
+
+    for (i, inline) in inlines.iter().enumerate() {
+        let next = inlines.get(i + 1).and_then(leading_char);
+        match &inline.kind {
+            InlineKind::Text(value) => {
+                let line_start = match context {
+                    InlineContext::Lines => formatted.is_empty() || formatted.ends_with('\n'),
+                    InlineContext::Item => formatted.ends_with('\n'),
+                    _ => false,
+                };
+                formatted.push_str(&escape_text(
+                    value,
+                    source_escapes(source(text, inline.span), value),
+                    context,
+                    line_start,
+                    next,
+                ));
+            }
+            InlineKind::Code(code) => formatted.push_str(&format_code_span(code)),
+            InlineKind::Strong(children) => {
+                let content = format_inlines(children, text, context);
+                formatted.push_str(&format!("**{}**", content));
+            }
+            InlineKind::Emphasis(children) => {
+                let content = format_inlines(children, text, context);
+                let delimiter = emphasis_delimiter(&content, formatted.chars().last(), next);
+                formatted.push_str(&format!("{}{}{}", delimiter, content, delimiter));
+            }
+            InlineKind::Strikethrough(children) => {
+                let content = format_inlines(children, text, context);
+                formatted.push_str(&format!("~~{}~~", content));
+            }
+            InlineKind::Underline(children) => {
+                let content = format_inlines(children, text, context);
+                formatted.push_str(&format!("__{}__", content));
+            }
+            InlineKind::Link {
+                url,
+                title,
+                children,
+            } => {
+                let content = format_inlines(children, text, context);
+                formatted.push_str(&format!(
+                    "[{}]({}{})",
+                    content,
+                    format_destination(url),
+                    format_title(title)
+                ));
+            }
+            InlineKind::Image { url, title, alt } => formatted.push_str(&format!(
+                "![{}]({}{})",
+                alt,
+                format_destination(url),
+                format_title(title)
+            )),
+            InlineKind::FootnoteReference { label } => formatted.push_str(&format!("[^{}]", label)),
+            InlineKind::LineBreak => formatted.push('\n'),
+        }
+    }
+
+    formatted
+}
+
+/// Returns the first character written for an inline element, before its delimiter is chosen.
+fn leading_char(inline: &Inline) -> Option<char> {
+    match &inline.kind {
+        InlineKind::Text(value) => value
+            .chars()
+            .next()
+            .map(|c| match ESCAPED_CHARS.contains(&c) {
+                true => '\\',
+                false => c,
+            }),
+        InlineKind::Code(_) => Some('`'),
+        InlineKind::Strong(_) | InlineKind::Emphasis(_) => Some('*'),
+        InlineKind::Strikethrough(_) => Some('~'),
+        InlineKind::Underline(_) => Some('_'),
+        InlineKind::Link { .. } | InlineKind::FootnoteReference { .. } => Some('['),
+        InlineKind::Image { .. } => Some('!'),
+        InlineKind::LineBreak => Some('\n'),
+    }
+}
+
+/// Chooses the delimiter of italic text: `*`, unless it would touch another `*` and be read as a bold delimiter.
+///
+/// # Arguments
+/// * `content` - The formatted content of the italic text.
+/// * `previous` - The last character written before the italic text.
+/// * `next` - The first character written after the italic text.
+///
+/// # Returns
+/// The delimiter of the italic text.
+fn emphasis_delimiter(content: &str, previous: Option<char>, next: Option<char>) -> char {
+    let touches = |delimiter: char| {
+        content.starts_with(delimiter)
+            || content.ends_with(delimiter)
+            || previous == Some(delimiter)
+            || next == Some(delimiter)
+    };
+
+    match touches('*') && !touches('_') {
+        true => '_',
+        false => '*',
+    }
+}
+
+/// Finds which characters of a text were escaped in the markdown input.
+///
+/// # Arguments
+/// * `source` - The part of the markdown input from which the text was parsed.
+/// * `value` - The text.
+///
+/// # Returns
+/// Whether every character of the text was escaped, or `None` if the text doesn't match its source.
+fn source_escapes(source: &str, value: &str) -> Option<Vec<bool>> {
+    let mut chars = Vec::new();
+    let mut escapes = Vec::new();
+    let mut source_chars = source.chars().peekable();
+
+    while let Some(c) = source_chars.next() {
+        match source_chars.peek() {
+            Some(&next) if c == '\\' && !is_whitespace(next) => {
+                chars.push(next);
+                escapes.push(true);
+                source_chars.next();
+            }
+            _ => {
+                chars.push(c);
+                escapes.push(false);
+            }
+        }
+    }
+
+    value.chars().eq(chars).then_some(escapes)
+}
+
+/// Escapes the characters of a text which would otherwise be parsed as markdown syntax.
+///
+/// # Arguments
+/// * `value` - The text.
+/// * `escapes` - Which characters were escaped in the markdown input, if they are known.
+/// * `context` - Where the text is written.
+/// * `line_start` - Whether the text starts a line of a paragraph.
+/// * `next` - The first character written after the text.
+///
+/// # Returns
+/// The escaped text.
+fn escape_text(
+    value: &str,
+    escapes: Option<Vec<bool>>,
+    context: InlineContext,
+    line_start: bool,
+    next: Option<char>,
+) -> String {
+    let chars: Vec<char> = value.chars().collect();
+    let mut escaped = String::new();
+    let mut line_start = line_start;
+    // The number of digits at the start of the current line, which may be the number of an ordered list item.
+    let mut list_number: Option<usize> = None;
+
+    for (i, &c) in chars.iter().enumerate() {
+        let rest = &chars[i..];
+        let block_start = line_start && !is_whitespace(c) && starts_block(rest);
+        let list_marker = matches!(c, '.' | ')')
+            && list_number.is_some_and(|digits| digits <= 9)
+            && rest.get(1).is_none_or(|&c| is_whitespace(c));
+
+        list_number = match (line_start, list_number) {
+            _ if !c.is_ascii_digit() => None,
+            (true, _) => Some(1),
+            (false, Some(digits)) => Some(digits + 1),
+            (false, None) => None,
+        };
+        line_start = match c {
+            '\n' => matches!(context, InlineContext::Lines | InlineContext::Item),
+            c => line_start && is_whitespace(c),
+        };
+
+        let escape = escapes.as_ref().is_some_and(|escapes| escapes[i])
+            || ESCAPED_CHARS.contains(&c)
+            || (c == '|' && context == InlineContext::Cell)
+            || (c == '#' && context == InlineContext::Heading)
+            || (c == '!' && i + 1 == chars.len() && next == Some('['))
+            || block_start
+            || list_marker;
+
+        if escape {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+
+    escaped
+}
+
+/// Returns whether a character is whitespace for the grammar (`ws`): a space separator or a tab.
+fn is_whitespace(c: char) -> bool {
+    c == '\t' || (c.is_whitespace() && !c.is_control() && !matches!(c, '\u{2028}' | '\u{2029}'))
+}
+
+/// Returns whether the rest of a line, which starts with its first non-whitespace character, would start a block.
+/// Ordered list markers are escaped separately, by their `.` or `)`.
+fn starts_block(rest: &[char]) -> bool {
+    let second = rest.get(1).copied();
+    match rest.first() {
+        Some('#' | '>' | '=' | '|' | '+') => true,
+        Some('-') => second.is_none_or(|c| is_whitespace(c) || c == '-'),
+        Some('–') => second == Some('–'),
+        _ => false,
+    }
+}
+
+/// Formats an inline code span, enclosed in a backtick run which doesn't appear in its content.
+fn format_code_span(code: &str) -> String {
+    let runs: Vec<usize> = code
+        .split(|c| c != '`')
+        .map(str::len)
+        .filter(|length| *length > 0)
+        .collect();
+    let length = (1..).find(|length| !runs.contains(length)).unwrap_or(1);
+    let fence = "`".repeat(length);
+
+    let padded = code.starts_with('`')
+        || code.ends_with('`')
+        || (code.starts_with(' ') && code.ends_with(' ') && !code.chars().all(|c| c == ' '));
+    match padded {
+        true => format!("{} {} {}", fence, code, fence),
+        false => format!("{}{}{}", fence, code, fence),
+    }
+}
+
+/// Formats the destination of an inline link or image, enclosed in `<` and `>` if it can't be written as it is.
+fn format_destination(url: &str) -> String {
+    let mut in_parentheses = false;
+    let mut plain = !url.is_empty() && !url.starts_with('<');
+
+    for c in url.chars() {
+        match c {
+            c if c.is_whitespace() => plain = false,
+            '(' if !in_parentheses => in_parentheses = true,
+            ')' if in_parentheses => in_parentheses = false,
+            ')' => plain = false,
+            _ => {}
+        }
+    }
+
+    match plain && !in_parentheses {
+        true => String::from(url),
+        false => format!("<{}>", url),
+    }
+}
+
+/// Formats the optional title of a link, enclosed in quotes which don't appear in it.
+fn format_title(title: &Option<String>) -> String {
+    let Some(title) = title else {
+        return String::new();
+    };
+
+    match (title.contains('"'), title.contains('\'')) {
+        (false, _) => format!(" \"{}\"", title),
+        (true, false) => format!(" '{}'", title),
+        (true, true) => format!(" ({})", title),
+    }
+}
+
+/// Returns a copy of the block with empty spans, so blocks parsed from different texts can be compared.
+fn without_spans(block: &Block) -> Block {
+    let mut block = block.clone();
+    clear_block(&mut block);
+    block
+}
+
+/// Clears spans of a block and of all its nodes.
+fn clear_block(block: &mut Block) {
+    block.span = Span::default();
+    match &mut block.kind {
+        BlockKind::Heading { children, .. }
+        | BlockKind::Paragraph { children }
+        | BlockKind::FootnoteDefinition { children, .. } => clear_inlines(children),
+        BlockKind::Quote { children } => children.iter_mut().for_each(clear_block),
+        BlockKind::List(list) => clear_list(list),
+        BlockKind::Table { header, rows, .. } => {
+            for cell in header.iter_mut().chain(rows.iter_mut().flatten()) {
+                cell.span = Span::default();
+                clear_inlines(&mut cell.children);
+            }
+        }
+        BlockKind::CodeBlock { .. }
+        | BlockKind::HorizontalRule
+        | BlockKind::EmptyLine
+        | BlockKind::LinkDefinition { .. } => {}
+    }
+}
+
+/// Clears spans of a list and of its items.
+fn clear_list(list: &mut List) {
+    list.span = Span::default();
+    for item in &mut list.items {
+        item.span = Span::default();
+        clear_inlines(&mut item.children);
+        if let Some(nested) = &mut item.nested {
+            clear_list(nested);
+        }
+        item.blocks.iter_mut().for_each(clear_block);
+    }
+}
+
+/// Clears spans of inline elements and of their children.
+fn clear_inlines(inlines: &mut [Inline]) {
+    for inline in inlines {
+        inline.span = Span::default();
+        match &mut inline.kind {
+            InlineKind::Strong(children)
+            | InlineKind::Emphasis(children)
+            | InlineKind::Strikethrough(children)
+            | InlineKind::Underline(children)
+            | InlineKind::Link { children, .. } => clear_inlines(children),
+            _ => {}
+        }
+    }
+}
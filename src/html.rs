@@ -0,0 +1,920 @@
+use std::{collections::HashMap, convert::Infallible, fmt, io};
+
+use crate::{
+    ast::{
+        collect_footnotes, normalize_label, Alignment, Block, BlockKind, Document, Inline,
+        InlineKind, List, Span, TableCell,
+    },
+    events::{Event, Tag},
+};
+
+/// Options of HTML rendering.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct HtmlOptions {
+    sourcepos: bool,
+}
+
+impl HtmlOptions {
+    /// Creates the default options.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets whether block elements get a `data-sourcepos="3:1-5:12"` attribute with the lines and columns
+    /// of their first and last characters in the markdown input.
+    pub fn sourcepos(mut self, enabled: bool) -> Self {
+        self.sourcepos = enabled;
+        self
+    }
+}
+
+/// Renders nodes of a document tree to HTML, one method for every kind of node.
+/// Every method has a default HTML implementation, so a renderer overrides only the nodes it changes
+/// (e.g., links with `rel="noopener"` or code blocks with custom markup).
+///
+/// Children of a node are rendered first and passed to its method as `content`. `attributes` contains the
+/// `data-sourcepos` attribute with a leading space if it's enabled in the options, or an empty string.
+/// Raw values, such as text, URLs and titles, are passed as they were written in the markdown input,
+/// use [`escape_html`] and [`encode_url`] to place them into HTML.
+pub trait Renderer {
+    /// Renders a heading of the given `level` (1-6).
+    fn heading(&mut self, level: u8, attributes: &str, content: &str) -> String {
+        format!("<h{}{}>{}</h{}>", level, attributes, content, level)
+    }
+
+    /// Renders a paragraph.
+    fn paragraph(&mut self, attributes: &str, content: &str) -> String {
+        format!("<p{}>{}</p>", attributes, content)
+    }
+
+    /// Renders a fenced code block with its optional language and literal content.
+    fn code_block(&mut self, lang: Option<&str>, code: &str, attributes: &str) -> String {
+        match lang {
+            Some(lang) => format!(
+                "<pre{}><code class=\"language-{}\">{}</code></pre>",
+                attributes,
+                escape_html(lang),
+                html_escape::encode_text(code)
+            ),
+            None => format!(
+                "<pre{}><code>{}</code></pre>",
+                attributes,
+                html_escape::encode_text(code)
+            ),
+        }
+    }
+
+    /// Renders a blockquote, its `content` contains the rendered blocks of the quote.
+    fn blockquote(&mut self, attributes: &str, content: &str) -> String {
+        format!("<blockquote{}>{}</blockquote>", attributes, content)
+    }
+
+    /// Renders an ordered or unordered list, its `content` contains the rendered items.
+    fn list(&mut self, ordered: bool, start: u64, attributes: &str, content: &str) -> String {
+        match (ordered, start) {
+            (false, _) => format!("<ul{}>{}</ul>", attributes, content),
+            (true, 1) => format!("<ol{}>{}</ol>", attributes, content),
+            (true, start) => format!("<ol start=\"{}\"{}>{}</ol>", start, attributes, content),
+        }
+    }
+
+    /// Renders a list item with an optional task checkbox, its `content` includes the rendered nested list and blocks.
+    fn list_item(&mut self, checked: Option<bool>, attributes: &str, content: &str) -> String {
+        let checkbox = match checked {
+            Some(true) => "<input type=\"checkbox\" checked disabled> ",
+            Some(false) => "<input type=\"checkbox\" disabled> ",
+            None => "",
+        };
+        format!("<li{}>{}{}</li>", attributes, checkbox, content)
+    }
+
+    /// Renders a table, its `content` contains the rendered head and body.
+    fn table(&mut self, attributes: &str, content: &str) -> String {
+        format!("<table{}>{}</table>", attributes, content)
+    }
+
+    /// Renders the head of a table, its `content` contains the rendered header row.
+    fn table_head(&mut self, content: &str) -> String {
+        format!("<thead>{}</thead>", content)
+    }
+
+    /// Renders the body of a table, it's rendered only if the table has body rows.
+    fn table_body(&mut self, content: &str) -> String {
+        format!("<tbody>{}</tbody>", content)
+    }
+
+    /// Renders a row of a table, its `content` contains the rendered cells.
+    fn table_row(&mut self, content: &str) -> String {
+        format!("<tr>{}</tr>", content)
+    }
+
+    /// Renders a cell of a table with the alignment of its column.
+    fn table_cell(
+        &mut self,
+        header: bool,
+        alignment: Alignment,
+        attributes: &str,
+        content: &str,
+    ) -> String {
+        let tag = if header { "th" } else { "td" };
+        let style = match alignment {
+            Alignment::Left => " style=\"text-align:left\"",
+            Alignment::Center => " style=\"text-align:center\"",
+            Alignment::Right => " style=\"text-align:right\"",
+            Alignment::None => "",
+        };
+        format!("<{}{}{}>{}</{}>", tag, style, attributes, content, tag)
+    }
+
+    /// Renders a horizontal rule.
+    fn horizontal_rule(&mut self, attributes: &str) -> String {
+        format!("<hr{}>", attributes)
+    }
+
+    /// Renders an empty line between blocks.
+    fn empty_line(&mut self) -> String {
+        String::from("<br/>")
+    }
+
+    /// Renders literal text.
+    fn text(&mut self, text: &str) -> String {
+        String::from(html_escape::encode_text(text))
+    }
+
+    /// Renders an inline code span with its literal content.
+    fn code(&mut self, code: &str) -> String {
+        format!("<code>{}</code>", html_escape::encode_text(code))
+    }
+
+    /// Renders bold text.
+    fn strong(&mut self, content: &str) -> String {
+        format!("<strong>{}</strong>", content)
+    }
+
+    /// Renders italic text.
+    fn emphasis(&mut self, content: &str) -> String {
+        format!("<em>{}</em>", content)
+    }
+
+    /// Renders strikethrough text.
+    fn strikethrough(&mut self, content: &str) -> String {
+        format!("<del>{}</del>", content)
+    }
+
+    /// Renders underlined text.
+    fn underline(&mut self, content: &str) -> String {
+        format!("<u>{}</u>", content)
+    }
+
+    /// Renders a link, its `content` contains the rendered link text.
+    fn link(&mut self, url: &str, title: Option<&str>, content: &str) -> String {
+        format!(
+            "<a href=\"{}\"{}>{}</a>",
+            encode_url(url),
+            title_attribute(title),
+            content
+        )
+    }
+
+    /// Renders an image with its alternative text.
+    fn image(&mut self, url: &str, title: Option<&str>, alt: &str) -> String {
+        format!(
+            "<img src=\"{}\" alt=\"{}\"{}>",
+            encode_url(url),
+            escape_html(alt),
+            title_attribute(title)
+        )
+    }
+
+    /// Renders a line break between lines of a paragraph.
+    fn line_break(&mut self) -> String {
+        String::from("<br>")
+    }
+
+    /// Renders a reference to a footnote. Footnotes are numbered in the order of their first reference,
+    /// `reference` is the number of this reference to the footnote, starting from 1.
+    fn footnote_reference(&mut self, number: usize, reference: usize) -> String {
+        format!(
+            "<sup class=\"footnote-ref\"><a href=\"#fn-{}\" id=\"{}\">{}</a></sup>",
+            number,
+            footnote_ref_id(number, reference),
+            number
+        )
+    }
+
+    /// Renders a single footnote of the footnotes section with back-links to its `references`.
+    fn footnote(&mut self, number: usize, references: usize, content: &str) -> String {
+        let back_links: Vec<String> = (1..=references)
+            .map(|reference| {
+                format!(
+                    "<a href=\"#{}\" class=\"footnote-backref\">↩</a>",
+                    footnote_ref_id(number, reference)
+                )
+            })
+            .collect();
+
+        format!(
+            "<li id=\"fn-{}\">{} {}</li>",
+            number,
+            content,
+            back_links.join(" ")
+        )
+    }
+
+    /// Renders the section with all referenced footnotes, its `content` contains the rendered footnotes.
+    fn footnotes(&mut self, content: &str) -> String {
+        format!(
+            "<section class=\"footnotes\"><ol>{}</ol></section>",
+            content
+        )
+    }
+}
+
+/// The renderer which uses the default HTML implementations of all nodes.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct HtmlRenderer;
+
+impl Renderer for HtmlRenderer {}
+
+/// State of a document shared between nodes while it is rendered to HTML.
+struct HtmlContext<'a, 'r> {
+    /// Options of the rendering.
+    options: HtmlOptions,
+    /// The renderer of the nodes.
+    renderer: &'r mut dyn Renderer,
+    /// Footnote definitions of the document, by their normalized label.
+    footnotes: HashMap<String, &'a [Inline]>,
+    /// Labels of referenced footnotes in the order of their first reference, with the number of references.
+    footnote_order: Vec<(String, usize)>,
+}
+
+/// Renders the `document` tree to HTML, returning a vector of HTML lines, one for every block.
+/// If the document references footnotes, a section with them is appended as the last line.
+///
+/// # Arguments
+/// * `document` - The document tree to render.
+///
+/// # Returns
+/// A vector of HTML lines.
+pub fn to_html(document: &Document) -> Vec<String> {
+    to_html_with_options(document, &HtmlOptions::default())
+}
+
+/// Renders the `document` tree to HTML with the given `options`, returning a vector of HTML lines, one for every block.
+/// If the document references footnotes, a section with them is appended as the last line.
+///
+/// # Arguments
+/// * `document` - The document tree to render.
+/// * `options` - Options of the rendering.
+///
+/// # Returns
+/// A vector of HTML lines.
+pub fn to_html_with_options(document: &Document, options: &HtmlOptions) -> Vec<String> {
+    to_html_with_renderer(document, options, &mut HtmlRenderer)
+}
+
+/// Renders the `document` tree with the given `renderer`, returning a vector of rendered lines, one for every block.
+/// If the document references footnotes, a section with them is appended as the last line.
+///
+/// # Arguments
+/// * `document` - The document tree to render.
+/// * `options` - Options of the rendering.
+/// * `renderer` - The renderer of the nodes.
+///
+/// # Returns
+/// A vector of rendered lines.
+pub fn to_html_with_renderer(
+    document: &Document,
+    options: &HtmlOptions,
+    renderer: &mut dyn Renderer,
+) -> Vec<String> {
+    let mut html_lines = Vec::new();
+    let result = render_lines(document, options, renderer, |line| {
+        html_lines.push(line);
+        Ok::<(), Infallible>(())
+    });
+
+    match result {
+        Ok(()) => html_lines,
+        Err(never) => match never {},
+    }
+}
+
+/// Renders the `document` tree with the given `renderer` and writes it into the `writer` as it's rendered,
+/// one line for every block, without collecting the whole output in memory.
+///
+/// # Arguments
+/// * `document` - The document tree to render.
+/// * `options` - Options of the rendering.
+/// * `renderer` - The renderer of the nodes.
+/// * `writer` - The writer into which the HTML is written, e.g. a `BufWriter` of a file or stdout.
+///
+/// # Errors
+/// - Returns an `io::Error` if writing into the `writer` fails.
+///
+/// # Returns
+/// A result indicating success or an `io::Error`.
+pub fn write_html<W: io::Write + ?Sized>(
+    document: &Document,
+    options: &HtmlOptions,
+    renderer: &mut dyn Renderer,
+    writer: &mut W,
+) -> io::Result<()> {
+    render_lines(document, options, renderer, |line| {
+        writer.write_all(line.as_bytes())?;
+        writer.write_all(b"\n")
+    })
+}
+
+/// Renders the `document` tree with the given `renderer` and writes it into the `writer` as it's rendered,
+/// one line for every block. It's the same as [`write_html`] for string buffers and formatters.
+///
+/// # Arguments
+/// * `document` - The document tree to render.
+/// * `options` - Options of the rendering.
+/// * `renderer` - The renderer of the nodes.
+/// * `writer` - The writer into which the HTML is written, e.g. a `String`.
+///
+/// # Errors
+/// - Returns a `fmt::Error` if writing into the `writer` fails.
+///
+/// # Returns
+/// A result indicating success or a `fmt::Error`.
+pub fn write_html_fmt<W: fmt::Write + ?Sized>(
+    document: &Document,
+    options: &HtmlOptions,
+    renderer: &mut dyn Renderer,
+    writer: &mut W,
+) -> fmt::Result {
+    render_lines(document, options, renderer, |line| {
+        writer.write_str(&line)?;
+        writer.write_char('\n')
+    })
+}
+
+/// Renders the `document` tree block by block, passing every rendered line to `write_line` as soon as it's rendered.
+/// If the document references footnotes, a section with them is passed as the last line.
+///
+/// # Arguments
+/// * `document` - The document tree to render.
+/// * `options` - Options of the rendering.
+/// * `renderer` - The renderer of the nodes.
+/// * `write_line` - The function which receives rendered lines.
+///
+/// # Errors
+/// - Returns the first error returned by `write_line`.
+///
+/// # Returns
+/// A result indicating success or the error of `write_line`.
+fn render_lines<E>(
+    document: &Document,
+    options: &HtmlOptions,
+    renderer: &mut dyn Renderer,
+    mut write_line: impl FnMut(String) -> Result<(), E>,
+) -> Result<(), E> {
+    let mut ctx = HtmlContext {
+        options: options.clone(),
+        renderer,
+        footnotes: HashMap::new(),
+        footnote_order: Vec::new(),
+    };
+    collect_footnotes(&document.blocks, &mut ctx.footnotes);
+
+    for block in &document.blocks {
+        let html = render_block(block, &mut ctx);
+        // Blocks such as footnote definitions don't produce any output in place.
+        if !html.is_empty() {
+            write_line(html)?;
+        }
+    }
+
+    if let Some(section) = render_footnotes(&mut ctx) {
+        write_line(section)?;
+    }
+
+    Ok(())
+}
+
+/// Renders markdown `events` to HTML, returning a vector of HTML lines, one for every top-level block.
+/// The events may come from [`crate::Events`] or from any iterator adapter over them.
+///
+/// # Arguments
+/// * `events` - The events to render.
+///
+/// # Returns
+/// A vector of HTML lines.
+pub fn events_to_html<I: IntoIterator<Item = Event>>(events: I) -> Vec<String> {
+    let mut html_lines = Vec::new();
+    let result = render_event_lines(events, &mut HtmlRenderer, |line| {
+        html_lines.push(line);
+        Ok::<(), Infallible>(())
+    });
+
+    match result {
+        Ok(()) => html_lines,
+        Err(never) => match never {},
+    }
+}
+
+/// Renders markdown `events` with the given `renderer` and writes them into the `writer` as they're rendered,
+/// one line for every top-level block.
+///
+/// # Arguments
+/// * `events` - The events to render.
+/// * `renderer` - The renderer of the elements.
+/// * `writer` - The writer into which the HTML is written.
+///
+/// # Errors
+/// - Returns an `io::Error` if writing into the `writer` fails.
+///
+/// # Returns
+/// A result indicating success or an `io::Error`.
+pub fn write_html_events<I: IntoIterator<Item = Event>, W: io::Write + ?Sized>(
+    events: I,
+    renderer: &mut dyn Renderer,
+    writer: &mut W,
+) -> io::Result<()> {
+    render_event_lines(events, renderer, |line| {
+        writer.write_all(line.as_bytes())?;
+        writer.write_all(b"\n")
+    })
+}
+
+/// Renders markdown `events`, passing the HTML of every top-level block to `write_line` as soon as it ends.
+/// Content of an element is collected until its end and passed to the `renderer` as a whole.
+/// Footnote definitions are collected and the section with the referenced ones is passed as the last line.
+/// Elements which aren't closed at the end of the events are closed automatically.
+///
+/// # Arguments
+/// * `events` - The events to render.
+/// * `renderer` - The renderer of the elements.
+/// * `write_line` - The function which receives rendered lines.
+///
+/// # Errors
+/// - Returns the first error returned by `write_line`.
+///
+/// # Returns
+/// A result indicating success or the error of `write_line`.
+fn render_event_lines<I: IntoIterator<Item = Event>, E>(
+    events: I,
+    renderer: &mut dyn Renderer,
+    mut write_line: impl FnMut(String) -> Result<(), E>,
+) -> Result<(), E> {
+    let mut open: Vec<(Tag, String)> = Vec::new();
+    let mut footnotes: HashMap<String, String> = HashMap::new();
+    let mut footnote_order: Vec<(String, usize)> = Vec::new();
+    let mut events = events.into_iter();
+
+    loop {
+        let html = match events.next() {
+            Some(Event::Start(tag)) => {
+                open.push((tag, String::new()));
+                continue;
+            }
+            Some(Event::End(_)) => match open.pop() {
+                Some((tag, content)) => render_tag(tag, content, renderer, &mut footnotes),
+                None => continue,
+            },
+            // Code blocks and images get their literal text, other elements get escaped text.
+            Some(Event::Text(text) | Event::Code(text))
+                if matches!(
+                    open.last(),
+                    Some((Tag::CodeBlock(_) | Tag::Image { .. }, _))
+                ) =>
+            {
+                text
+            }
+            Some(Event::Text(text)) => renderer.text(&text),
+            Some(Event::Code(code)) => renderer.code(&code),
+            Some(Event::SoftBreak | Event::HardBreak) => renderer.line_break(),
+            Some(Event::EmptyLine) => renderer.empty_line(),
+            Some(Event::Rule) => renderer.horizontal_rule(""),
+            Some(Event::FootnoteReference(label)) => {
+                let label = normalize_label(&label);
+                let index = match footnote_order.iter().position(|(used, _)| *used == label) {
+                    Some(index) => index,
+                    None => {
+                        footnote_order.push((label, 0));
+                        footnote_order.len() - 1
+                    }
+                };
+                footnote_order[index].1 += 1;
+                renderer.footnote_reference(index + 1, footnote_order[index].1)
+            }
+            None => match open.pop() {
+                Some((tag, content)) => render_tag(tag, content, renderer, &mut footnotes),
+                None => break,
+            },
+        };
+
+        match open.last_mut() {
+            Some((_, content)) => content.push_str(&html),
+            // Footnote definitions don't produce any output in place.
+            None if !html.is_empty() => write_line(html)?,
+            None => {}
+        }
+    }
+
+    if !footnote_order.is_empty() {
+        let content: String = footnote_order
+            .iter()
+            .enumerate()
+            .map(|(index, (label, references))| {
+                let text_html = footnotes.get(label).map(String::as_str).unwrap_or_default();
+                renderer.footnote(index + 1, *references, text_html)
+            })
+            .collect();
+        write_line(renderer.footnotes(&content))?;
+    }
+
+    Ok(())
+}
+
+/// Renders an element which has ended, with the collected HTML of its content.
+/// A footnote definition is stored to be rendered in the footnotes section instead.
+///
+/// # Arguments
+/// * `tag` - The tag of the element.
+/// * `content` - The rendered content of the element, or the literal text of code blocks and images.
+/// * `renderer` - The renderer of the elements.
+/// * `footnotes` - Rendered footnote definitions by their normalized label.
+///
+/// # Returns
+/// The HTML of the element, or an empty string for a footnote definition.
+fn render_tag(
+    tag: Tag,
+    content: String,
+    renderer: &mut dyn Renderer,
+    footnotes: &mut HashMap<String, String>,
+) -> String {
+    match tag {
+        Tag::Heading(level) => renderer.heading(level, "", &content),
+        Tag::Paragraph => renderer.paragraph("", &content),
+        Tag::CodeBlock(lang) => renderer.code_block(lang.as_deref(), &content, ""),
+        Tag::BlockQuote => renderer.blockquote("", &content),
+        Tag::List(None) => renderer.list(false, 1, "", &content),
+        Tag::List(Some(start)) => renderer.list(true, start, "", &content),
+        Tag::Item(checked) => renderer.list_item(checked, "", &content),
+        Tag::Table(_) => renderer.table("", &content),
+        Tag::TableHead => renderer.table_head(&content),
+        Tag::TableBody => renderer.table_body(&content),
+        Tag::TableRow => renderer.table_row(&content),
+        Tag::TableCell { header, alignment } => {
+            renderer.table_cell(header, alignment, "", &content)
+        }
+        Tag::Emphasis => renderer.emphasis(&content),
+        Tag::Strong => renderer.strong(&content),
+        Tag::Strikethrough => renderer.strikethrough(&content),
+        Tag::Underline => renderer.underline(&content),
+        Tag::Link { url, title } => renderer.link(&url, title.as_deref(), &content),
+        Tag::Image { url, title } => renderer.image(&url, title.as_deref(), &content),
+        Tag::FootnoteDefinition(label) => {
+            footnotes.entry(normalize_label(&label)).or_insert(content);
+            String::new()
+        }
+    }
+}
+
+/// Renders a single block to HTML.
+///
+/// # Arguments
+/// * `block` - The block to render.
+/// * `ctx` - The state of the document which is being rendered.
+///
+/// # Returns
+/// The HTML representation of the block.
+fn render_block(block: &Block, ctx: &mut HtmlContext) -> String {
+    let pos = sourcepos_attribute(&block.span, ctx);
+
+    match &block.kind {
+        BlockKind::Heading { level, children } => {
+            let content = render_inlines(children, ctx);
+            ctx.renderer.heading(*level, &pos, &content)
+        }
+        BlockKind::Paragraph { children } => {
+            let content = render_inlines(children, ctx);
+            ctx.renderer.paragraph(&pos, &content)
+        }
+        BlockKind::CodeBlock { lang, content } => {
+            ctx.renderer.code_block(lang.as_deref(), content, &pos)
+        }
+        BlockKind::Quote { children } => {
+            let content: String = children
+                .iter()
+                .map(|block| render_block(block, ctx))
+                .collect();
+            ctx.renderer.blockquote(&pos, &content)
+        }
+        BlockKind::List(list) => render_list(list, ctx),
+        BlockKind::Table {
+            alignments,
+            header,
+            rows,
+        } => render_table(alignments, header, rows, &pos, ctx),
+        BlockKind::HorizontalRule => ctx.renderer.horizontal_rule(&pos),
+        BlockKind::EmptyLine => ctx.renderer.empty_line(),
+        BlockKind::FootnoteDefinition { .. } | BlockKind::LinkDefinition { .. } => String::new(),
+    }
+}
+
+/// Builds the `data-sourcepos` attribute of an element, if it's enabled in the options.
+/// The attribute contains the line and column of the first and the last character of the element (e.g., `3:1-5:12`).
+///
+/// # Arguments
+/// * `span` - The span of the rendered node.
+/// * `ctx` - The state of the document which is being rendered.
+///
+/// # Returns
+/// The attribute with a leading space, or an empty string if it's disabled.
+fn sourcepos_attribute(span: &Span, ctx: &HtmlContext) -> String {
+    if !ctx.options.sourcepos {
+        return String::new();
+    }
+
+    // The end of a span points right after its last character, an empty span ends where it starts.
+    let (end_line, end_column) = match span.end.offset > span.start.offset && span.end.column > 1 {
+        true => (span.end.line, span.end.column - 1),
+        false => (span.start.line, span.start.column),
+    };
+
+    format!(
+        " data-sourcepos=\"{}:{}-{}:{}\"",
+        span.start.line, span.start.column, end_line, end_column
+    )
+}
+
+/// Renders an ordered or unordered list with its items, nested lists and blocks of the items to HTML.
+///
+/// # Arguments
+/// * `list` - The list to render.
+/// * `ctx` - The state of the document which is being rendered.
+///
+/// # Returns
+/// The HTML representation of the list.
+fn render_list(list: &List, ctx: &mut HtmlContext) -> String {
+    let mut items = String::new();
+
+    for item in &list.items {
+        let mut content = render_inlines(&item.children, ctx);
+        if let Some(nested) = &item.nested {
+            content.push_str(&render_list(nested, ctx));
+        }
+        for block in &item.blocks {
+            content.push_str(&render_block(block, ctx));
+        }
+        let pos = sourcepos_attribute(&item.span, ctx);
+        items.push_str(&ctx.renderer.list_item(item.checked, &pos, &content));
+    }
+
+    let pos = sourcepos_attribute(&list.span, ctx);
+    ctx.renderer.list(list.ordered, list.start, &pos, &items)
+}
+
+/// Renders a table with its header and body rows to HTML.
+///
+/// # Arguments
+/// * `alignments` - Alignments of the table columns.
+/// * `header` - Cells of the header row.
+/// * `rows` - Body rows of the table.
+/// * `pos` - The `data-sourcepos` attribute of the table.
+/// * `ctx` - The state of the document which is being rendered.
+///
+/// # Returns
+/// The HTML representation of the table.
+fn render_table(
+    alignments: &[Alignment],
+    header: &[TableCell],
+    rows: &[Vec<TableCell>],
+    pos: &str,
+    ctx: &mut HtmlContext,
+) -> String {
+    let header_row = render_table_row(header, true, alignments, ctx);
+    let mut html_content = ctx.renderer.table_head(&header_row);
+
+    if !rows.is_empty() {
+        let body: String = rows
+            .iter()
+            .map(|row| render_table_row(row, false, alignments, ctx))
+            .collect();
+        html_content.push_str(&ctx.renderer.table_body(&body));
+    }
+
+    ctx.renderer.table(pos, &html_content)
+}
+
+/// Renders a single row of a table to HTML.
+///
+/// # Arguments
+/// * `cells` - Cells of the row.
+/// * `header` - Whether the row is the header row.
+/// * `alignments` - Alignments of the table columns.
+/// * `ctx` - The state of the document which is being rendered.
+///
+/// # Returns
+/// The HTML representation of the row.
+fn render_table_row(
+    cells: &[TableCell],
+    header: bool,
+    alignments: &[Alignment],
+    ctx: &mut HtmlContext,
+) -> String {
+    let mut content = String::new();
+    for (i, cell) in cells.iter().enumerate() {
+        let alignment = alignments.get(i).copied().unwrap_or(Alignment::None);
+        let pos = sourcepos_attribute(&cell.span, ctx);
+        let cell_content = render_inlines(&cell.children, ctx);
+        content.push_str(
+            &ctx.renderer
+                .table_cell(header, alignment, &pos, &cell_content),
+        );
+    }
+
+    ctx.renderer.table_row(&content)
+}
+
+/// Renders inline elements to HTML.
+///
+/// # Arguments
+/// * `inlines` - The inline elements to render.
+/// * `ctx` - The state of the document which is being rendered.
+///
+/// # Returns
+/// The HTML representation of the inline elements.
+fn render_inlines(inlines: &[Inline], ctx: &mut HtmlContext) -> String {
+    inlines
+        .iter()
+        .map(|inline| render_inline(inline, ctx))
+        .collect()
+}
+
+/// Renders a single inline element to HTML.
+///
+/// # Arguments
+/// * `inline` - The inline element to render.
+/// * `ctx` - The state of the document which is being rendered.
+///
+/// # Returns
+/// The HTML representation of the inline element.
+fn render_inline(inline: &Inline, ctx: &mut HtmlContext) -> String {
+    match &inline.kind {
+        InlineKind::Text(text) => ctx.renderer.text(text),
+        InlineKind::Code(code) => ctx.renderer.code(code),
+        InlineKind::Strong(children) => {
+            let content = render_inlines(children, ctx);
+            ctx.renderer.strong(&content)
+        }
+        InlineKind::Emphasis(children) => {
+            let content = render_inlines(children, ctx);
+            ctx.renderer.emphasis(&content)
+        }
+        InlineKind::Strikethrough(children) => {
+            let content = render_inlines(children, ctx);
+            ctx.renderer.strikethrough(&content)
+        }
+        InlineKind::Underline(children) => {
+            let content = render_inlines(children, ctx);
+            ctx.renderer.underline(&content)
+        }
+        InlineKind::Link {
+            url,
+            title,
+            children,
+        } => {
+            let content = render_inlines(children, ctx);
+            ctx.renderer.link(url, title.as_deref(), &content)
+        }
+        InlineKind::Image { url, title, alt } => ctx.renderer.image(url, title.as_deref(), alt),
+        InlineKind::FootnoteReference { label } => render_footnote_reference(label, ctx),
+        InlineKind::LineBreak => ctx.renderer.line_break(),
+    }
+}
+
+/// Escapes the HTML special characters (`&`, `<`, `>` and `"`) of a text,
+/// so it can be safely placed inside HTML text or a double-quoted attribute.
+///
+/// # Arguments
+/// * `text` - The text to escape.
+///
+/// # Returns
+/// The escaped text.
+pub fn escape_html(text: &str) -> String {
+    String::from(html_escape::encode_double_quoted_attribute(text))
+}
+
+/// Encodes a URL, so it can be safely placed inside an HTML attribute.
+/// Characters which are not allowed in URLs (e.g., whitespace, quotes or non-ASCII characters) are percent-encoded,
+/// existing percent-encoded sequences are kept as they are.
+///
+/// # Arguments
+/// * `url` - The URL as written in the markdown input.
+///
+/// # Returns
+/// The encoded URL.
+pub fn encode_url(url: &str) -> String {
+    const ALLOWED: &[u8] = b"-._~:/?#[]@!$&'()*+,;=";
+
+    let bytes = url.as_bytes();
+    let mut encoded = String::new();
+
+    for (i, byte) in bytes.iter().enumerate() {
+        let is_escape_sequence = *byte == b'%'
+            && bytes.get(i + 1).is_some_and(u8::is_ascii_hexdigit)
+            && bytes.get(i + 2).is_some_and(u8::is_ascii_hexdigit);
+
+        if byte.is_ascii_alphanumeric() || ALLOWED.contains(byte) || is_escape_sequence {
+            encoded.push(char::from(*byte));
+        } else {
+            encoded.push_str(&format!("%{:02X}", byte));
+        }
+    }
+
+    escape_html(&encoded)
+}
+
+/// Builds the `title` attribute of a link or an image.
+///
+/// # Arguments
+/// * `title` - The optional title as written in the markdown input.
+///
+/// # Returns
+/// The encoded attribute with a leading space, or an empty string if there is no title.
+fn title_attribute(title: Option<&str>) -> String {
+    match title {
+        Some(title) => format!(" title=\"{}\"", escape_html(title)),
+        None => String::new(),
+    }
+}
+
+/// Renders a footnote reference and numbers it in the order of first references.
+///
+/// # Arguments
+/// * `label` - The label of the referenced footnote.
+/// * `ctx` - The state of the document which is being rendered.
+///
+/// # Returns
+/// The HTML representation of the footnote reference.
+fn render_footnote_reference(label: &str, ctx: &mut HtmlContext) -> String {
+    let label = normalize_label(label);
+    let index = match ctx
+        .footnote_order
+        .iter()
+        .position(|(used, _)| *used == label)
+    {
+        Some(index) => index,
+        None => {
+            ctx.footnote_order.push((label, 0));
+            ctx.footnote_order.len() - 1
+        }
+    };
+    ctx.footnote_order[index].1 += 1;
+
+    let reference = ctx.footnote_order[index].1;
+    ctx.renderer.footnote_reference(index + 1, reference)
+}
+
+/// Renders the section with all referenced footnotes, each with back-links to its references.
+///
+/// # Arguments
+/// * `ctx` - The state of the document which is being rendered.
+///
+/// # Returns
+/// The HTML of the footnotes section, or `None` if no footnotes are referenced.
+fn render_footnotes(ctx: &mut HtmlContext) -> Option<String> {
+    if ctx.footnote_order.is_empty() {
+        return None;
+    }
+
+    let mut html_content = String::new();
+
+    // Footnotes may reference other footnotes, which adds them to the end of the order.
+    let mut index = 0;
+    while index < ctx.footnote_order.len() {
+        let children = ctx
+            .footnotes
+            .get(&ctx.footnote_order[index].0)
+            .copied()
+            .unwrap_or_default();
+        let text_html = render_inlines(children, ctx);
+
+        let references = ctx.footnote_order[index].1;
+        html_content.push_str(&ctx.renderer.footnote(index + 1, references, &text_html));
+        index += 1;
+    }
+
+    Some(ctx.renderer.footnotes(&html_content))
+}
+
+/// Builds the id of a footnote reference, which is unique for every reference of the same footnote.
+///
+/// # Arguments
+/// * `number` - The number of the footnote.
+/// * `reference` - The number of the reference to this footnote, starting from 1.
+///
+/// # Returns
+/// The id of the reference, e.g. `fnref-1` or `fnref-1-2`.
+fn footnote_ref_id(number: usize, reference: usize) -> String {
+    match reference {
+        1 => format!("fnref-{}", number),
+        _ => format!("fnref-{}-{}", number, reference),
+    }
+}
@@ -0,0 +1,84 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{parse_document, Document, ErrorParse};
+
+/// The version of the JSON schema of the document tree.
+/// It's increased whenever the schema changes in a way which isn't compatible with previous versions.
+pub const JSON_SCHEMA_VERSION: u32 = 1;
+
+/// A document tree with the version of its JSON schema, the root object of the JSON representation.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct JsonDocument {
+    /// The version of the schema, equal to [`JSON_SCHEMA_VERSION`].
+    pub version: u32,
+    /// The document tree.
+    pub document: Document,
+}
+
+/// A borrowed `JsonDocument`, which serializes a document without copying it.
+#[derive(Serialize)]
+struct JsonDocumentRef<'a> {
+    version: u32,
+    document: &'a Document,
+}
+
+/// Parses provided `text` in markdown format and serializes its document tree to JSON.
+/// The output is a `JsonDocument` object: `{"version": 1, "document": {"blocks": [...], "span": {...}}}`.
+///
+/// # Arguments
+/// * `text` - A string slice containing the markdown content.
+///
+/// # Errors
+/// - Returns an `ErrorParse::ParsingError` on an internal error of parsing, if the parse tree doesn't have the expected shape.
+/// - Returns an `ErrorParse::JsonError` if the document can't be serialized.
+///
+/// # Returns
+/// A result containing either the JSON string or a `ErrorParse` if the serialization fails.
+pub fn str_to_json(text: &str) -> Result<String, ErrorParse> {
+    document_to_json(&parse_document(text)?)
+}
+
+/// Serializes the `document` tree to JSON with the current version of the schema.
+///
+/// # Arguments
+/// * `document` - The document tree to serialize.
+///
+/// # Errors
+/// - Returns an `ErrorParse::JsonError` if the document can't be serialized.
+///
+/// # Returns
+/// A result containing either the JSON string or a `ErrorParse` if the serialization fails.
+pub fn document_to_json(document: &Document) -> Result<String, ErrorParse> {
+    let json_document = JsonDocumentRef {
+        version: JSON_SCHEMA_VERSION,
+        document,
+    };
+
+    serde_json::to_string(&json_document)
+        .map_err(|err| ErrorParse::JsonError(format!("Failed to serialize a document: {}", err)))
+}
+
+/// Deserializes a document tree from the `json` produced by [`str_to_json`] or [`document_to_json`].
+///
+/// # Arguments
+/// * `json` - A string slice containing the JSON representation of a document.
+///
+/// # Errors
+/// - Returns an `ErrorParse::JsonError` if the JSON doesn't match the schema or has a different schema version.
+///
+/// # Returns
+/// A result containing either the `Document` or a `ErrorParse` if the deserialization fails.
+pub fn document_from_json(json: &str) -> Result<Document, ErrorParse> {
+    let json_document: JsonDocument = serde_json::from_str(json).map_err(|err| {
+        ErrorParse::JsonError(format!("Failed to deserialize a document: {}", err))
+    })?;
+
+    if json_document.version != JSON_SCHEMA_VERSION {
+        return Err(ErrorParse::JsonError(format!(
+            "Unsupported document schema version {}, expected {}",
+            json_document.version, JSON_SCHEMA_VERSION
+        )));
+    }
+
+    Ok(json_document.document)
+}
@@ -0,0 +1,476 @@
+use std::collections::HashMap;
+
+use crate::ast::{
+    collect_footnotes, normalize_label, Alignment, Block, BlockKind, Document, Inline, InlineKind,
+    List, TableCell,
+};
+
+/// Counters of the `enumerate` environment by its nesting depth, used to set the number of the first item.
+const ENUMERATE_COUNTERS: [&str; 4] = ["enumi", "enumii", "enumiii", "enumiv"];
+
+/// Options of LaTeX rendering.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LatexOptions {
+    standalone: bool,
+    listings: bool,
+}
+
+impl LatexOptions {
+    /// Creates the default options: a fragment of a document with code blocks in `verbatim` environments.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets whether the result is a complete document, wrapped in a preamble with `\documentclass`,
+    /// the required packages and the `document` environment. By default the result is a fragment,
+    /// which can be included into another document with `\input`.
+    pub fn standalone(mut self, enabled: bool) -> Self {
+        self.standalone = enabled;
+        self
+    }
+
+    /// Sets whether code blocks are written as `lstlisting` environments of the `listings` package,
+    /// with the language of the code block. By default code blocks are `verbatim` environments.
+    pub fn listings(mut self, enabled: bool) -> Self {
+        self.listings = enabled;
+        self
+    }
+}
+
+/// Renders the `document` tree to a LaTeX fragment, see [`to_latex_with_options`].
+///
+/// # Arguments
+/// * `document` - The document tree to render.
+///
+/// # Returns
+/// The LaTeX source of the document.
+pub fn to_latex(document: &Document) -> String {
+    to_latex_with_options(document, &LatexOptions::default())
+}
+
+/// Renders the `document` tree to LaTeX with the given `options`.
+///
+/// Headings are written as `\section`, `\subsection` and `\subsubsection` (deeper headings as `\paragraph`
+/// and `\subparagraph`), styled text as `\textbf`, `\emph`, `\underline` and `\sout`, links as `\href`
+/// and images as `\includegraphics`. Footnotes are written as `\footnote` at their first reference.
+/// Special characters of LaTeX in the text are escaped, code blocks are kept verbatim.
+///
+/// The result requires the `graphicx`, `ulem` and `hyperref` packages (and `listings` if it's enabled, or if a code
+/// block contains `\end{verbatim}`), which are included by the preamble of a standalone document.
+///
+/// # Arguments
+/// * `document` - The document tree to render.
+/// * `options` - Options of the rendering.
+///
+/// # Returns
+/// The LaTeX source of the document.
+pub fn to_latex_with_options(document: &Document, options: &LatexOptions) -> String {
+    let mut writer = LatexWriter {
+        options,
+        footnotes: HashMap::new(),
+        numbers: HashMap::new(),
+        listings: options.listings,
+    };
+    collect_footnotes(&document.blocks, &mut writer.footnotes);
+    let body = writer.render_blocks(&document.blocks, 0);
+
+    let mut latex = String::new();
+    if options.standalone {
+        latex.push_str("\\documentclass{article}\n");
+        latex.push_str("\\usepackage[T1]{fontenc}\n");
+        latex.push_str("\\usepackage[utf8]{inputenc}\n");
+        latex.push_str("\\usepackage{graphicx}\n");
+        latex.push_str("\\usepackage[normalem]{ulem}\n");
+        if writer.listings {
+            latex.push_str("\\usepackage{listings}\n");
+        }
+        latex.push_str("\\usepackage{hyperref}\n\n");
+        latex.push_str("\\begin{document}\n\n");
+    }
+    if !body.is_empty() {
+        latex.push_str(&body);
+        latex.push('\n');
+    }
+    if options.standalone {
+        latex.push_str("\n\\end{document}\n");
+    }
+
+    latex
+}
+
+/// State of rendering a document to LaTeX.
+struct LatexWriter<'a> {
+    options: &'a LatexOptions,
+    /// Contents of footnote definitions by their normalized label.
+    footnotes: HashMap<String, &'a [Inline]>,
+    /// Numbers of footnotes which are already written, by their normalized label.
+    numbers: HashMap<String, usize>,
+    /// Whether the `listings` package is used, either by the options or for a code block which can't be `verbatim`.
+    listings: bool,
+}
+
+impl LatexWriter<'_> {
+    /// Renders blocks, separated by an empty line. Blocks without LaTeX source are skipped.
+    ///
+    /// # Arguments
+    /// * `blocks` - The blocks.
+    /// * `enumerate_depth` - The number of `enumerate` environments which contain the blocks.
+    ///
+    /// # Returns
+    /// The LaTeX source of the blocks without a trailing line break.
+    fn render_blocks(&mut self, blocks: &[Block], enumerate_depth: usize) -> String {
+        blocks
+            .iter()
+            .filter_map(|block| self.render_block(block, enumerate_depth))
+            .collect::<Vec<_>>()
+            .join("\n\n")
+    }
+
+    /// Renders a single block.
+    ///
+    /// # Arguments
+    /// * `block` - The block.
+    /// * `enumerate_depth` - The number of `enumerate` environments which contain the block.
+    ///
+    /// # Returns
+    /// The LaTeX source of the block, or `None` if the block isn't rendered.
+    fn render_block(&mut self, block: &Block, enumerate_depth: usize) -> Option<String> {
+        let latex = match &block.kind {
+            BlockKind::Heading { level, children } => {
+                let command = match level {
+                    1 => "section",
+                    2 => "subsection",
+                    3 => "subsubsection",
+                    4 => "paragraph",
+                    _ => "subparagraph",
+                };
+                format!("\\{}{{{}}}", command, self.render_inlines(children))
+            }
+            BlockKind::Paragraph { children } => self.render_inlines(children),
+            BlockKind::CodeBlock { lang, content } => self.render_code_block(lang, content),
+            BlockKind::Quote { children } => format!(
+                "\\begin{{quote}}\n{}\n\\end{{quote}}",
+                self.render_blocks(children, enumerate_depth)
+            ),
+            BlockKind::List(list) => self.render_list(list, enumerate_depth),
+            BlockKind::Table {
+                alignments,
+                header,
+                rows,
+            } => self.render_table(alignments, header, rows),
+            BlockKind::HorizontalRule => String::from("\\hrule"),
+            BlockKind::EmptyLine
+            | BlockKind::FootnoteDefinition { .. }
+            | BlockKind::LinkDefinition { .. } => return None,
+        };
+
+        Some(latex)
+    }
+
+    /// Renders a code block as a `verbatim` or an `lstlisting` environment. If the content contains the end of
+    /// the environment, the other environment is used. If it contains the ends of both environments,
+    /// its lines are written as escaped text in a typewriter font.
+    ///
+    /// # Arguments
+    /// * `lang` - The language of the code block.
+    /// * `content` - The literal content of the code block.
+    ///
+    /// # Returns
+    /// The LaTeX source of the code block.
+    fn render_code_block(&mut self, lang: &Option<String>, content: &str) -> String {
+        let content = content.strip_suffix('\n').unwrap_or(content);
+        let verbatim = !content.contains("\\end{verbatim}");
+        let listing = !content.contains("\\end{lstlisting}");
+
+        if listing && (self.options.listings || !verbatim) {
+            self.listings = true;
+            let lang = lang
+                .as_deref()
+                .map(|lang| lang.replace(|c: char| !c.is_alphanumeric() && c != '+', ""))
+                .filter(|lang| !lang.is_empty() && self.options.listings);
+            return match lang {
+                Some(lang) => format!(
+                    "\\begin{{lstlisting}}[language={}]\n{}\n\\end{{lstlisting}}",
+                    lang, content
+                ),
+                None => format!("\\begin{{lstlisting}}\n{}\n\\end{{lstlisting}}", content),
+            };
+        }
+        if verbatim {
+            return format!("\\begin{{verbatim}}\n{}\n\\end{{verbatim}}", content);
+        }
+
+        let lines: Vec<String> = content
+            .lines()
+            .map(|line| format!("\\mbox{{}}{}", escape_latex(line).replace(' ', "~")))
+            .collect();
+        format!(
+            "\\begin{{flushleft}}\n\\ttfamily\n{}\n\\end{{flushleft}}",
+            lines.join(" \\\\\n")
+        )
+    }
+
+    /// Renders a list as an `itemize` or an `enumerate` environment. Task list items are labeled with a checkbox.
+    /// Blocks of an item are separated from its text by an empty line, so they start new paragraphs of the item.
+    ///
+    /// # Arguments
+    /// * `list` - The list.
+    /// * `enumerate_depth` - The number of `enumerate` environments which contain the list.
+    ///
+    /// # Returns
+    /// The LaTeX source of the list.
+    fn render_list(&mut self, list: &List, enumerate_depth: usize) -> String {
+        let environment = if list.ordered { "enumerate" } else { "itemize" };
+        let depth = enumerate_depth + usize::from(list.ordered);
+        let mut lines = vec![format!("\\begin{{{}}}", environment)];
+
+        if list.ordered && list.start != 1 {
+            if let Some(counter) = ENUMERATE_COUNTERS.get(enumerate_depth) {
+                lines.push(format!(
+                    "  \\setcounter{{{}}}{{{}}}",
+                    counter,
+                    list.start.saturating_sub(1)
+                ));
+            }
+        }
+
+        for item in &list.items {
+            let label = match item.checked {
+                Some(true) => "[{[x]}]",
+                Some(false) => "[{[ ]}]",
+                None => "",
+            };
+            lines.push(format!(
+                "  \\item{}{{}} {}",
+                label,
+                self.render_inlines(&item.children).replace('\n', "\n  ")
+            ));
+            if let Some(nested) = &item.nested {
+                let nested = self.render_list(nested, depth);
+                lines.extend(indent_lines(&nested));
+            }
+            let blocks = self.render_blocks(&item.blocks, depth);
+            if !blocks.is_empty() {
+                lines.push(String::new());
+                lines.extend(indent_lines(&blocks));
+            }
+        }
+
+        lines.push(format!("\\end{{{}}}", environment));
+        lines.join("\n")
+    }
+
+    /// Renders a table as a `tabular` environment with a line under the bold header row.
+    ///
+    /// # Arguments
+    /// * `alignments` - Alignments of the columns.
+    /// * `header` - Cells of the header row.
+    /// * `rows` - Cells of the body rows.
+    ///
+    /// # Returns
+    /// The LaTeX source of the table.
+    fn render_table(
+        &mut self,
+        alignments: &[Alignment],
+        header: &[TableCell],
+        rows: &[Vec<TableCell>],
+    ) -> String {
+        let columns = std::iter::once(header.len())
+            .chain(rows.iter().map(Vec::len))
+            .chain(std::iter::once(alignments.len()))
+            .max()
+            .unwrap_or_default();
+        let spec: String = (0..columns)
+            .map(|column| match alignments.get(column) {
+                Some(Alignment::Center) => 'c',
+                Some(Alignment::Right) => 'r',
+                _ => 'l',
+            })
+            .collect();
+
+        let header_cells: Vec<String> = header
+            .iter()
+            .map(|cell| format!("\\textbf{{{}}}", self.render_inlines(&cell.children)))
+            .collect();
+        let mut lines = vec![
+            format!("\\begin{{tabular}}{{{}}}", spec),
+            format!("  {} \\\\", row_text(&header_cells)),
+            String::from("  \\hline"),
+        ];
+        for row in rows {
+            let cells: Vec<String> = row
+                .iter()
+                .map(|cell| self.render_inlines(&cell.children))
+                .collect();
+            lines.push(format!("  {} \\\\", row_text(&cells)));
+        }
+        lines.push(String::from("\\end{tabular}"));
+
+        lines.join("\n")
+    }
+
+    /// Renders inline elements. Line breaks between lines of a paragraph are kept.
+    ///
+    /// # Arguments
+    /// * `inlines` - The inline elements.
+    ///
+    /// # Returns
+    /// The LaTeX source of the elements.
+    fn render_inlines(&mut self, inlines: &[Inline]) -> String {
+        let mut latex = String::new();
+
+        for inline in inlines {
+            match &inline.kind {
+                InlineKind::Text(text) => latex.push_str(&escape_latex(text)),
+                InlineKind::Code(code) => {
+                    latex.push_str(&format!("\\texttt{{{}}}", escape_latex(code)))
+                }
+                InlineKind::Strong(children) => {
+                    latex.push_str(&format!("\\textbf{{{}}}", self.render_inlines(children)))
+                }
+                InlineKind::Emphasis(children) => {
+                    latex.push_str(&format!("\\emph{{{}}}", self.render_inlines(children)))
+                }
+                InlineKind::Underline(children) => {
+                    latex.push_str(&format!("\\underline{{{}}}", self.render_inlines(children)))
+                }
+                InlineKind::Strikethrough(children) => {
+                    latex.push_str(&format!("\\sout{{{}}}", self.render_inlines(children)))
+                }
+                InlineKind::Link { url, children, .. } => latex.push_str(&format!(
+                    "\\href{{{}}}{{{}}}",
+                    escape_url(url),
+                    self.render_inlines(children)
+                )),
+                InlineKind::Image { url, .. } => {
+                    latex.push_str(&format!("\\includegraphics{{{}}}", escape_url(url)))
+                }
+                InlineKind::FootnoteReference { label } => {
+                    latex.push_str(&self.render_footnote(label))
+                }
+                InlineKind::LineBreak => latex.push('\n'),
+            }
+        }
+
+        latex
+    }
+
+    /// Renders a footnote reference. The first reference to a footnote is written as `\footnote`
+    /// with the content of the definition, later references as `\footnotemark` with its number.
+    ///
+    /// # Arguments
+    /// * `label` - The label of the footnote.
+    ///
+    /// # Returns
+    /// The LaTeX source of the reference.
+    fn render_footnote(&mut self, label: &str) -> String {
+        let key = normalize_label(label);
+        if let Some(number) = self.numbers.get(&key) {
+            return format!("\\footnotemark[{}]", number);
+        }
+
+        self.numbers.insert(key.clone(), self.numbers.len() + 1);
+        let content = self.footnotes.get(&key).copied().unwrap_or_default();
+        format!("\\footnote{{{}}}", self.render_inlines(content))
+    }
+}
+
+/// Indents the lines of the LaTeX `source` by two spaces. The content of `verbatim` and `lstlisting` environments
+/// is kept as it is, since its whitespace is printed, and empty lines are kept empty.
+///
+/// # Arguments
+/// * `source` - The LaTeX source.
+///
+/// # Returns
+/// The indented lines.
+fn indent_lines(source: &str) -> Vec<String> {
+    let mut environment: Option<&str> = None;
+
+    source
+        .lines()
+        .map(|line| {
+            let command = line.trim_start();
+            if let Some(name) = environment {
+                if command != format!("\\end{{{}}}", name) {
+                    return String::from(line);
+                }
+                environment = None;
+            } else if command.starts_with("\\begin{verbatim}") {
+                environment = Some("verbatim");
+            } else if command.starts_with("\\begin{lstlisting}") {
+                environment = Some("lstlisting");
+            }
+
+            match line.is_empty() {
+                true => String::new(),
+                false => format!("  {}", line),
+            }
+        })
+        .collect()
+}
+
+/// Joins cells of a table row. A row which starts with `[` or `*` is prefixed with `{}`,
+/// so it isn't read as the optional argument or the star of the `\\` at the end of the previous row.
+fn row_text(cells: &[String]) -> String {
+    let row = cells.join(" & ");
+    match row.starts_with(['[', '*']) {
+        true => format!("{{}}{}", row),
+        false => row,
+    }
+}
+
+/// Escapes special characters of LaTeX in text, so they are printed literally.
+///
+/// # Arguments
+/// * `text` - The text.
+///
+/// # Returns
+/// The escaped text.
+pub fn escape_latex(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+
+    for c in text.chars() {
+        match c {
+            '\\' => escaped.push_str("\\textbackslash{}"),
+            '{' | '}' | '$' | '&' | '#' | '%' | '_' => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            '^' => escaped.push_str("\\textasciicircum{}"),
+            '~' => escaped.push_str("\\textasciitilde{}"),
+            '<' => escaped.push_str("\\textless{}"),
+            '>' => escaped.push_str("\\textgreater{}"),
+            '|' => escaped.push_str("\\textbar{}"),
+            c => escaped.push(c),
+        }
+    }
+
+    escaped
+}
+
+/// Escapes a URL for `\href` and `\includegraphics`: `#`, `%` and `~` are escaped,
+/// while backslashes and braces, which can't be escaped in a URL, are percent-encoded.
+///
+/// # Arguments
+/// * `url` - The URL.
+///
+/// # Returns
+/// The escaped URL.
+fn escape_url(url: &str) -> String {
+    let mut escaped = String::with_capacity(url.len());
+
+    for c in url.chars() {
+        match c {
+            '#' | '%' | '~' => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            '\\' => escaped.push_str("\\%5C"),
+            '{' => escaped.push_str("\\%7B"),
+            '}' => escaped.push_str("\\%7D"),
+            c => escaped.push(c),
+        }
+    }
+
+    escaped
+}
@@ -1,16 +1,52 @@
+mod ast;
+mod error;
+mod events;
+mod format;
+mod html;
+#[cfg(feature = "serde")]
+mod json;
+mod latex;
+mod man;
+#[cfg(feature = "serde")]
+mod mdast;
+mod options;
+mod terminal;
+mod text;
+
 use std::{
-    fs::{File, OpenOptions},
-    io::{BufRead, BufReader, Error as ioError, Write},
+    fs::{self, File, OpenOptions},
+    io::{self, BufRead, BufReader, BufWriter, Error as ioError, Write},
     path::Path,
 };
 
-use pest::{
-    iterators::{Pair, Pairs},
-    Parser,
-};
+use pest::{iterators::Pairs, Parser};
 use pest_derive::Parser;
 use thiserror::Error;
 
+pub use ast::{
+    Alignment, Block, BlockKind, Document, Inline, InlineKind, List, ListItem, Position, Span,
+    TableCell,
+};
+pub use error::SyntaxError;
+pub use events::{Event, Events, Tag};
+pub use format::{format_markdown, format_markdown_with_warnings};
+pub use html::{
+    encode_url, escape_html, events_to_html, to_html, to_html_with_options, to_html_with_renderer,
+    write_html, write_html_events, write_html_fmt, HtmlOptions, HtmlRenderer, Renderer,
+};
+#[cfg(feature = "serde")]
+pub use json::{
+    document_from_json, document_to_json, str_to_json, JsonDocument, JSON_SCHEMA_VERSION,
+};
+pub use latex::{escape_latex, to_latex, to_latex_with_options, LatexOptions};
+pub use man::{escape_roff, to_man, to_man_with_options, ManOptions};
+// The mdast export serializes nodes with `serde_json`, so it requires the `serde` feature.
+#[cfg(feature = "serde")]
+pub use mdast::{document_to_mdast, str_to_mdast};
+pub use options::ParseOptions;
+pub use terminal::{to_terminal, TerminalOptions};
+pub use text::{to_text, to_text_with_options, TextOptions};
+
 /// Parser structure which is used to parse text with the grammar defined in `src/grammar.pest`.
 /// It is automatically generated by `pest_derive` based on the specified grammar.
 #[derive(Parser)]
@@ -20,311 +56,333 @@ pub struct Grammar;
 /// Enum representing possible errors encountered during parsing or file operations.
 #[derive(Error, Debug)]
 pub enum ErrorParse {
-    /// Error when there is a parsing issue with the markdown input.
+    /// Error when the parse tree of the markdown input doesn't have the expected shape.
+    /// Lenient parsing accepts any input, so this is an internal error rather than a problem of the markdown.
     #[error("An error occurrred while parsing: {0}")]
     ParsingError(String),
 
+    /// Error when the markdown input doesn't match the grammar, with the location of the error.
+    #[error("An error occurrred while parsing: {0}")]
+    SyntaxError(#[from] SyntaxError),
+
+    /// Error when a document can't be serialized to JSON, or the JSON of a document can't be deserialized
+    /// (e.g., it doesn't match the schema or has another schema version). Returned only with the `serde` feature.
+    #[error("A JSON error occurrred: {0}")]
+    JsonError(String),
+
     /// Error when there is a file operation issue: file read/write.
     #[error("A file error occurrred: {0}")]
     FileError(#[from] ioError),
 }
 
+/// Enum representing problems in a markdown document which don't prevent it from being converted.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum ParseWarning {
+    /// A footnote is referenced, but never defined. The reference is kept as plain text.
+    #[error("Footnote [^{label}] referenced on line {line} is not defined")]
+    UndefinedFootnote { label: String, line: usize },
+
+    /// A footnote is defined, but never referenced. The definition is not rendered.
+    #[error("Footnote [^{label}] defined on line {line} is never referenced")]
+    UnusedFootnote { label: String, line: usize },
+
+    /// A part of the document can't be formatted without changing its meaning. The formatter keeps it unchanged.
+    #[error("{} can't be formatted without changing its meaning, it's kept unchanged", line_range(.span))]
+    Unformatted { span: Span },
+}
+
+/// Describes the lines covered by the `span`, e.g. `Line 3` or `Lines 3-5`.
+fn line_range(span: &Span) -> String {
+    match span.start.line == span.end.line {
+        true => format!("Line {}", span.start.line),
+        false => format!("Lines {}-{}", span.start.line, span.end.line),
+    }
+}
+
+/// A single task list item (e.g., `- [x] Done`) found in a markdown document.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TaskItem {
+    /// The markdown text of the task, without the list marker and the checkbox.
+    pub text: String,
+    /// Whether the checkbox of the task is marked.
+    pub checked: bool,
+    /// The line number (starting from 1) on which the task is located.
+    pub line: usize,
+}
+
 /// Converts provided `text` in markdown format to HTML, returning a vector of HTML lines.
+/// Delimiters without a matching closing delimiter (e.g., a stray `*` or an unclosed `**`) are kept as literal text,
+/// so any input can be converted.
 ///
 /// # Arguments
 /// * `text` - A string slice containing the markdown content. All lines in the text are separated by new line breaks.
 ///
 /// # Errors
-/// - Returns an `ErrorParse::ParsingError` if there's an error during a parsing process.
+/// - Malformed markdown doesn't fail, it's kept as literal text. An `ErrorParse::ParsingError` is returned only on an internal error, if the parse tree doesn't have the expected shape.
 ///
 /// # Returns
-/// A result containing either a vector of HTML lines or a `ErrorParse` if the parsing fails.
+/// A result containing either a vector of HTML lines or a `ErrorParse` on an internal error.
 pub fn str_to_html(text: &str) -> Result<Vec<String>, ErrorParse> {
-    let parsed_pairs =
-        parse_markdown(text).map_err(|e| ErrorParse::ParsingError(format!("{:?}", e)))?;
+    str_to_html_with_warnings(text).map(|(html_lines, _)| html_lines)
+}
 
-    let markdown_content = parsed_pairs
-        .into_iter()
-        .next()
-        .ok_or_else(|| ErrorParse::ParsingError(String::from("Expected markdown got nothing")))?;
+/// Converts provided `text` in markdown format to HTML like [`str_to_html`], but fails on unmatched delimiters
+/// instead of keeping them as literal text.
+///
+/// # Arguments
+/// * `text` - A string slice containing the markdown content. All lines in the text are separated by new line breaks.
+///
+/// # Errors
+/// - Returns an `ErrorParse::ParsingError` only on an internal error, if the parse tree doesn't have the expected shape.
+/// - Returns an `ErrorParse::SyntaxError` located at the first delimiter without a matching closing delimiter.
+///
+/// # Returns
+/// A result containing either a vector of HTML lines or a `ErrorParse` if the parsing fails.
+pub fn str_to_html_strict(text: &str) -> Result<Vec<String>, ErrorParse> {
+    str_to_html_with_options(text, &ParseOptions::new().strict(true))
+}
 
-    let html_lines: Result<Vec<String>, ErrorParse> = markdown_content
-        .into_inner()
-        .map(|pair| to_html(pair))
-        .collect();
+/// Converts provided `text` in markdown format to HTML, parsing only the markdown constructs enabled by the `options`.
+/// Disabled constructs are kept as literal text.
+///
+/// # Arguments
+/// * `text` - A string slice containing the markdown content. All lines in the text are separated by new line breaks.
+/// * `options` - Options which define the parsed markdown constructs.
+///
+/// # Errors
+/// - Returns an `ErrorParse::ParsingError` only on an internal error, if the parse tree doesn't have the expected shape.
+/// - Returns an `ErrorParse::SyntaxError` located at the first unmatched delimiter if the options are strict.
+///
+/// # Returns
+/// A result containing either a vector of HTML lines or a `ErrorParse` if the parsing fails.
+pub fn str_to_html_with_options(
+    text: &str,
+    options: &ParseOptions,
+) -> Result<Vec<String>, ErrorParse> {
+    let (document, _) = parse_document_with_options(text, options)?;
 
-    html_lines
+    Ok(to_html(&document))
 }
 
-/// Converts a `pest::iterators::Pair` representing a single rule into an HTML string.
+/// Converts provided `text` in markdown format to HTML, returning a vector of HTML lines and warnings about the document.
+/// If the document references footnotes, a section with them is appended as the last line.
 ///
 /// # Arguments
-/// * `pair` - A `pest::iterators::Pair` representing the parsed rule to convert.
+/// * `text` - A string slice containing the markdown content. All lines in the text are separated by new line breaks.
 ///
 /// # Errors
-/// - Returns an `ErrorParse::ParsingError` if there's an error during a parsing process.
+/// - Malformed markdown doesn't fail, it's kept as literal text. An `ErrorParse::ParsingError` is returned only on an internal error, if the parse tree doesn't have the expected shape.
 ///
 /// # Returns
-/// A result containing either the corresponding HTML string or an `ErrorParse` if the rule is unknown or an error occurs.
-pub fn to_html(pair: Pair<Rule>) -> Result<String, ErrorParse> {
-    match pair.as_rule() {
-        Rule::empty_line => Ok(String::from("<br/>")),
-        Rule::heading1 => Ok(format!("<h1>{}</h1>", parse_heading(pair)?)),
-        Rule::heading2 => Ok(format!("<h2>{}</h2>", parse_heading(pair)?)),
-        Rule::heading3 => Ok(format!("<h3>{}</h3>", parse_heading(pair)?)),
-        Rule::code_block => match parse_code_block(pair) {
-            Ok((lang, content)) => Ok(format!(
-                "<pre><code class=\"language-{}\">{}</code></pre>",
-                lang, content
-            )),
-            Err(err) => Err(err),
-        },
-        Rule::quote => Ok(format!("<blockquote>{}</blockquote>", parse_quote(pair)?)),
-        Rule::horizontal_rule => Ok(String::from("<hr>")),
-        Rule::paragraph => Ok(format!("<p>{}</p>", parse_paragraph(pair)?)),
-        Rule::inline_link => match parse_inline_link(pair) {
-            Ok((link_text, url)) => Ok(format!("<a href=\"{}\">{}</a>", url, link_text)),
-            Err(err) => Err(err),
-        },
-        Rule::inline_image => match parse_inline_image(pair) {
-            Ok((alt_text, url)) => Ok(format!("<img src=\"{}\" alt=\"{}\">", url, alt_text)),
-            Err(err) => Err(err),
-        },
-        Rule::bold => Ok(format!("<strong>{}</strong>", parse_styled_text(pair)?)),
-        Rule::italic => Ok(format!("<em>{}</em>", parse_styled_text(pair)?)),
-        Rule::strikethrough => Ok(format!("<del>{}</del>", parse_styled_text(pair)?)),
-        Rule::underline => Ok(format!("<u>{}</u>", parse_styled_text(pair)?)),
-        Rule::escaped => Ok(parse_escaped_char(pair)?),
-        Rule::content => Ok(String::from(html_escape::encode_text(pair.as_str()))),
-        Rule::plain_text => Ok(String::from(html_escape::encode_text(pair.as_str()))),
-        Rule::EOI => Ok(String::new()),
-        _ => Err(ErrorParse::ParsingError(format!(
-            "Unknown rule: {:#?}",
-            pair.as_rule()
-        ))),
-    }
+/// A result containing either a tuple of HTML lines and warnings or a `ErrorParse` on an internal error.
+pub fn str_to_html_with_warnings(
+    text: &str,
+) -> Result<(Vec<String>, Vec<ParseWarning>), ErrorParse> {
+    let (document, warnings) = parse_document_with_warnings(text)?;
+
+    Ok((to_html(&document), warnings))
 }
 
-/// Parses an escaped character from the markdown syntax, such as escaped special characters.
+/// Converts provided `text` in markdown format to plain text without any markup, see [`to_text`].
 ///
 /// # Arguments
-/// * `pair` - A `pest::iterators::Pair` representing the escaped character rule.
+/// * `text` - A string slice containing the markdown content.
 ///
 /// # Errors
-/// - Returns an `ErrorParse::ParsingError` if there's an error during a parsing process.
+/// - Malformed markdown doesn't fail, it's kept as literal text. An `ErrorParse::ParsingError` is returned only on an internal error, if the parse tree doesn't have the expected shape.
 ///
 /// # Returns
-/// A result containing the encoded HTML representation of the escaped character.
-fn parse_escaped_char(pair: Pair<Rule>) -> Result<String, ErrorParse> {
-    let mut inner = pair.into_inner();
-    let char_sym = inner
-        .next()
-        .ok_or_else(|| {
-            ErrorParse::ParsingError(String::from("Expected char rule inside an escaped"))
-        })?
-        .as_str();
-
-    let encoded_char = String::from(html_escape::encode_text(char_sym));
-    Ok(encoded_char)
+/// A result containing either the plain text or a `ErrorParse` on an internal error.
+pub fn str_to_text(text: &str) -> Result<String, ErrorParse> {
+    Ok(to_text(&parse_document(text)?))
 }
 
-/// Parses styled text such as bold, italic, strikethrough, or underline in the markdown input.
+/// Converts provided `text` in markdown format to a LaTeX fragment, see [`to_latex_with_options`].
 ///
 /// # Arguments
-/// * `pair` - A `pest::iterators::Pair` representing the styled text rule.
+/// * `text` - A string slice containing the markdown content.
 ///
 /// # Errors
-/// - Returns an `ErrorParse::ParsingError` if there's an error during a parsing process.
+/// - Malformed markdown doesn't fail, it's kept as literal text. An `ErrorParse::ParsingError` is returned only on an internal error, if the parse tree doesn't have the expected shape.
 ///
 /// # Returns
-/// A result containing the HTML string of the styled content.
-fn parse_styled_text(pair: Pair<Rule>) -> Result<String, ErrorParse> {
-    let inner = pair.into_inner();
-    let mut html_content = String::new();
-
-    for rule in inner {
-        let content = to_html(rule)?;
-        html_content.push_str(content.as_str());
-    }
+/// A result containing either the LaTeX source or a `ErrorParse` on an internal error.
+pub fn str_to_latex(text: &str) -> Result<String, ErrorParse> {
+    Ok(to_latex(&parse_document(text)?))
+}
 
-    Ok(html_content)
+/// Converts provided `text` in markdown format to a `man(7)` page, see [`to_man_with_options`].
+///
+/// # Arguments
+/// * `text` - A string slice containing the markdown content.
+///
+/// # Errors
+/// - Malformed markdown doesn't fail, it's kept as literal text. An `ErrorParse::ParsingError` is returned only on an internal error, if the parse tree doesn't have the expected shape.
+///
+/// # Returns
+/// A result containing either the roff source of the page or a `ErrorParse` on an internal error.
+pub fn str_to_man(text: &str) -> Result<String, ErrorParse> {
+    Ok(to_man(&parse_document(text)?))
 }
 
-/// Parses an inline image (e.g., `![alt_text](url)`) in the markdown input.
+/// Converts provided `text` in markdown format to styled text for a terminal, see [`to_terminal`].
 ///
 /// # Arguments
-/// * `pair` - A `pest::iterators::Pair` representing the inline image rule.
+/// * `text` - A string slice containing the markdown content.
+/// * `options` - Options of the rendering, e.g. [`TerminalOptions::from_env`].
 ///
 /// # Errors
-/// - Returns an `ErrorParse::ParsingError` if there's an error during a parsing process.
+/// - Malformed markdown doesn't fail, it's kept as literal text. An `ErrorParse::ParsingError` is returned only on an internal error, if the parse tree doesn't have the expected shape.
 ///
 /// # Returns
-/// A result containing a tuple of the encoded alt text and URL of the image.
-fn parse_inline_image(pair: Pair<Rule>) -> Result<(String, String), ErrorParse> {
-    let mut inner = pair.into_inner();
-    let alt_text = inner
-        .next()
-        .ok_or_else(|| {
-            ErrorParse::ParsingError(String::from(
-                "Expected link_text rule inside an inline_link",
-            ))
-        })?
-        .as_str();
-    let url = inner
-        .next()
-        .ok_or_else(|| ErrorParse::ParsingError(String::from("Expected url inside a inline_link")))?
-        .as_str();
-
-    let alt_text_encoded = String::from(html_escape::encode_text(alt_text));
-    let url_string = String::from(url);
-
-    Ok((alt_text_encoded, url_string))
+/// A result containing either the text for a terminal or a `ErrorParse` on an internal error.
+pub fn str_to_terminal(text: &str, options: &TerminalOptions) -> Result<String, ErrorParse> {
+    Ok(to_terminal(&parse_document(text)?, options))
 }
 
-/// Parses an inline link (e.g., `[link_text](url)`) in the markdown input.
+/// Parses provided `text` in markdown format into a document tree.
+/// Unmatched delimiters are kept as text nodes.
 ///
 /// # Arguments
-/// * `pair` - A `pest::iterators::Pair` representing the inline link rule.
+/// * `text` - A string slice containing the markdown content.
 ///
 /// # Errors
-/// - Returns an `ErrorParse::ParsingError` if there's an error during a parsing process.
+/// - Malformed markdown doesn't fail, it's kept as literal text. An `ErrorParse::ParsingError` is returned only on an internal error, if the parse tree doesn't have the expected shape.
 ///
 /// # Returns
-/// A result containing a tuple of the encoded link text and URL.
-fn parse_inline_link(pair: Pair<Rule>) -> Result<(String, String), ErrorParse> {
-    let mut inner = pair.into_inner();
-    let link_text = inner
-        .next()
-        .ok_or_else(|| {
-            ErrorParse::ParsingError(String::from(
-                "Expected link_text rule inside an inline_link",
-            ))
-        })?
-        .as_str();
-    let link_url = inner
-        .next()
-        .ok_or_else(|| ErrorParse::ParsingError(String::from("Expected url inside a inline_link")))?
-        .as_str();
-
-    let link_text_encoded = String::from(html_escape::encode_text(link_text));
-    let url_string = String::from(link_url);
-
-    Ok((link_text_encoded, url_string))
+/// A result containing either the `Document` or a `ErrorParse` on an internal error.
+pub fn parse_document(text: &str) -> Result<Document, ErrorParse> {
+    parse_document_with_warnings(text).map(|(document, _)| document)
 }
 
-/// Parses a paragraph from the markdown input.
-/// A paragraph is a collection of paragraph lines. Line containt either a plain_text, escaped character or styled_text.
+/// Parses provided `text` in markdown format into a document tree, failing on unmatched delimiters
+/// (e.g., `*`, `**`, `__` or `~~` without a closing pair) instead of keeping them as text.
 ///
 /// # Arguments
-/// * `pair` - A `pest::iterators::Pair` representing the paragraph rule.
+/// * `text` - A string slice containing the markdown content.
 ///
 /// # Errors
-/// - Returns an `ErrorParse::ParsingError` if there's an error during a parsing process.
+/// - Returns an `ErrorParse::ParsingError` only on an internal error, if the parse tree doesn't have the expected shape.
+/// - Returns an `ErrorParse::SyntaxError` located at the first delimiter without a matching closing delimiter.
 ///
 /// # Returns
-/// A result containing the HTML representation of the entire paragraph.
-fn parse_paragraph(pair: Pair<Rule>) -> Result<String, ErrorParse> {
-    let inner_lines = pair.into_inner();
-    let total_lines = inner_lines.len();
-    let mut html_content = String::new();
-
-    for (i, line) in inner_lines.enumerate() {
-        match line.as_rule() {
-            Rule::paragraph_line => {
-                let line_content = line
-                    .clone()
-                    .into_inner()
-                    .map(|text| to_html(text))
-                    .collect::<Result<String, ErrorParse>>()?;
-                html_content.push_str(line_content.as_str());
-
-                if i < total_lines - 1 {
-                    html_content.push_str("<br>");
-                }
-            }
-            _ => {
-                return Err(ErrorParse::ParsingError(format!(
-                    "Unexpected rule inside a paragraph: {:#?}",
-                    line.as_rule()
-                )));
-            }
-        }
-    }
+/// A result containing either the `Document` or a `ErrorParse` if the parsing fails.
+pub fn parse_document_strict(text: &str) -> Result<Document, ErrorParse> {
+    parse_document_with_options(text, &ParseOptions::new().strict(true))
+        .map(|(document, _)| document)
+}
 
-    Ok(html_content)
+/// Parses provided `text` in markdown format into a document tree, returning it with warnings about the document.
+/// Reference links and images are resolved against link definitions, references to undefined footnotes are kept as text.
+///
+/// # Arguments
+/// * `text` - A string slice containing the markdown content.
+///
+/// # Errors
+/// - Malformed markdown doesn't fail, it's kept as literal text. An `ErrorParse::ParsingError` is returned only on an internal error, if the parse tree doesn't have the expected shape.
+///
+/// # Returns
+/// A result containing either a tuple of the `Document` and warnings or a `ErrorParse` on an internal error.
+pub fn parse_document_with_warnings(
+    text: &str,
+) -> Result<(Document, Vec<ParseWarning>), ErrorParse> {
+    parse_document_with_options(text, &ParseOptions::new())
 }
 
-/// Parses a blockquote (e.g., `> quote text`) from the markdown input.
+/// Parses provided `text` in markdown format into a document tree, parsing only the markdown constructs enabled by the `options`.
+/// Disabled constructs are kept as literal text, e.g. a disabled heading becomes a paragraph.
 ///
 /// # Arguments
-/// * `pair` - A `pest::iterators::Pair` representing the blockquote rule.
+/// * `text` - A string slice containing the markdown content.
+/// * `options` - Options which define the parsed markdown constructs.
 ///
 /// # Errors
-/// - Returns an `ErrorParse::ParsingError` if there's an error during a parsing process.
+/// - Returns an `ErrorParse::ParsingError` only on an internal error, if the parse tree doesn't have the expected shape.
+/// - Returns an `ErrorParse::SyntaxError` located at the first unmatched delimiter if the options are strict.
 ///
 /// # Returns
-/// A result containing the HTML representation of the blockquote.
-fn parse_quote(pair: Pair<Rule>) -> Result<String, ErrorParse> {
-    let mut inner = pair.into_inner();
-    let paragraph = inner.next().ok_or_else(|| {
-        ErrorParse::ParsingError(String::from("Expected paragraph rule inside a quote"))
-    })?;
-    to_html(paragraph)
+/// A result containing either a tuple of the `Document` and warnings or a `ErrorParse` if the parsing fails.
+pub fn parse_document_with_options(
+    text: &str,
+    options: &ParseOptions,
+) -> Result<(Document, Vec<ParseWarning>), ErrorParse> {
+    ast::build_document(text, options)
 }
 
-/// Parses a code block from the markdown input.
-/// Code block always has a content and may have a language of the code.
+/// Parses provided `text` in markdown format and returns an iterator of its events
+/// (e.g., `Event::Start(Tag::Heading(1))`, `Event::Text`, `Event::End(Tag::Heading(1))`).
+/// Unmatched delimiters are kept as text.
 ///
 /// # Arguments
-/// * `pair` - A `pest::iterators::Pair` representing the code block rule.
+/// * `text` - A string slice containing the markdown content.
 ///
 /// # Errors
-/// - Returns an `ErrorParse::ParsingError` if there's an error during a parsing process.
+/// - Malformed markdown doesn't fail, it's kept as literal text. An `ErrorParse::ParsingError` is returned only on an internal error, if the parse tree doesn't have the expected shape.
 ///
 /// # Returns
-/// A result containing a tuple with the language and the HTML-encoded code content.
-fn parse_code_block(pair: Pair<Rule>) -> Result<(String, String), ErrorParse> {
-    let mut inner = pair.into_inner();
-    let code_lang = inner
-        .next()
-        .ok_or_else(|| {
-            ErrorParse::ParsingError(String::from("Expected code_lang rule iside an code block"))
-        })?
-        .as_str();
-    let code_content = inner
-        .next()
-        .ok_or_else(|| {
-            ErrorParse::ParsingError(String::from("Expected code content inside a code block"))
-        })?
-        .as_str();
-
-    let code_encoded = String::from(html_escape::encode_text(code_lang));
-    let content_encoded = String::from(html_escape::encode_text(code_content));
-
-    Ok((code_encoded, content_encoded))
+/// A result containing either the iterator of events or a `ErrorParse` on an internal error.
+pub fn parse_events(text: &str) -> Result<Events, ErrorParse> {
+    parse_document(text).map(Events::new)
 }
 
-/// Parses a heading (e.g., `# Heading 1`) from the markdown input.
+/// Collects all task list items (e.g., `- [ ] Task` or `- [x] Done`) from the provided markdown `text`.
+/// Tasks inside nested lists, blocks of list items and blockquotes are included, in the order they appear in the text.
 ///
 /// # Arguments
-/// * `pair` - A `pest::iterators::Pair` representing the heading rule.
+/// * `text` - A string slice containing the markdown content.
 ///
 /// # Errors
-/// - Returns an `ErrorParse::ParsingError` if there's an error during a parsing process.
+/// - Malformed markdown doesn't fail, it's kept as literal text. An `ErrorParse::ParsingError` is returned only on an internal error, if the parse tree doesn't have the expected shape.
 ///
 /// # Returns
-/// A result containing the HTML-encoded heading text.
-fn parse_heading(pair: Pair<Rule>) -> Result<String, ErrorParse> {
-    let mut inner = pair.into_inner();
-    let sngl_line_text = inner.next().ok_or_else(|| {
-        ErrorParse::ParsingError(String::from(
-            "Expected single_line_text rule inside a header",
-        ))
-    })?;
-
-    let text: &str = sngl_line_text.as_str();
-
-    Ok(String::from(html_escape::encode_text(text)))
+/// A result containing a vector of found tasks or an `ErrorParse` on an internal error.
+pub fn parse_tasks(text: &str) -> Result<Vec<TaskItem>, ErrorParse> {
+    let document = parse_document(text)?;
+    let mut tasks = Vec::new();
+    collect_tasks(text, &document.blocks, &mut tasks);
+
+    Ok(tasks)
+}
+
+/// Collects task list items from the `blocks` of a document, including the ones inside blockquotes.
+///
+/// # Arguments
+/// * `text` - The markdown content of the document.
+/// * `blocks` - Blocks of the document.
+/// * `tasks` - The vector to which found tasks are added.
+fn collect_tasks(text: &str, blocks: &[Block], tasks: &mut Vec<TaskItem>) {
+    for block in blocks {
+        match &block.kind {
+            BlockKind::List(list) => collect_list_tasks(text, list, tasks),
+            BlockKind::Quote { children } => collect_tasks(text, children, tasks),
+            _ => {}
+        }
+    }
+}
+
+/// Collects task list items from the items of a `list`, its nested lists and blocks of the items.
+///
+/// # Arguments
+/// * `text` - The markdown content of the document.
+/// * `list` - The list.
+/// * `tasks` - The vector to which found tasks are added.
+fn collect_list_tasks(text: &str, list: &List, tasks: &mut Vec<TaskItem>) {
+    for item in &list.items {
+        if let (Some(checked), Some(first), Some(last)) =
+            (item.checked, item.children.first(), item.children.last())
+        {
+            tasks.push(TaskItem {
+                text: String::from(&text[first.span.start.offset..last.span.end.offset]),
+                checked,
+                line: item.span.start.line,
+            });
+        }
+
+        if let Some(nested) = &item.nested {
+            collect_list_tasks(text, nested, tasks);
+        }
+        collect_tasks(text, &item.blocks, tasks);
+    }
 }
 
 /// Parses the given `input` markdown string and returns a `pest::iterators::Pairs` of rules.
@@ -334,12 +392,12 @@ fn parse_heading(pair: Pair<Rule>) -> Result<String, ErrorParse> {
 ///
 /// # Errors
 /// - Returns an `ErrorParse::ParsingError` if there's an error during a parsing process.
+/// - Returns an `ErrorParse::SyntaxError` if the markdown input doesn't match the grammar.
 ///
 /// # Returns
 /// A result containing either the parsed `pest::iterators::Pairs` or an `ErrorParse` if the parsing fails.
-pub fn parse_markdown(input: &str) -> Result<Pairs<Rule>, ErrorParse> {
-    return Grammar::parse(Rule::markdown, input)
-        .map_err(|err| ErrorParse::ParsingError(err.to_string()));
+pub fn parse_markdown(input: &str) -> Result<Pairs<'_, Rule>, ErrorParse> {
+    parse_by_rule(Rule::markdown, input)
 }
 
 /// Parses the `input` string according to a specific rule and returns the resulting pairs.
@@ -350,14 +408,17 @@ pub fn parse_markdown(input: &str) -> Result<Pairs<Rule>, ErrorParse> {
 ///
 /// # Errors
 /// - Returns an `ErrorParse::ParsingError` if there's an error during a parsing process.
+/// - Returns an `ErrorParse::SyntaxError` if the markdown input doesn't match the grammar.
 ///
 /// # Returns
 /// A result containing the parsed `pest::iterators::Pairs` or an `ErrorParse` if the parsing fails.
-pub fn parse_by_rule(rule: Rule, input: &str) -> Result<Pairs<Rule>, ErrorParse> {
-    return Grammar::parse(rule, input).map_err(|err| ErrorParse::ParsingError(err.to_string()));
+pub fn parse_by_rule(rule: Rule, input: &str) -> Result<Pairs<'_, Rule>, ErrorParse> {
+    Grammar::parse(rule, input)
+        .map_err(|err| ErrorParse::SyntaxError(SyntaxError::from_pest(input, err)))
 }
 
 /// Converts the markdown file at `md_path` to an HTML file at `html_path`.
+/// The HTML is written into the file as it's rendered, without collecting it in memory.
 ///
 /// # Arguments
 /// * `md_path` - The path to the markdown file.
@@ -369,7 +430,7 @@ pub fn parse_by_rule(rule: Rule, input: &str) -> Result<Pairs<Rule>, ErrorParse>
 ///
 /// # Errors
 /// - Returns an `ErrorParse::FileError` if there's an error while file r/w operation.
-/// - Returns an `ErrorParse::ParsingError` if there's an error during a parsing process.
+/// - Malformed markdown doesn't fail, it's kept as literal text. An `ErrorParse::ParsingError` is returned only on an internal error, if the parse tree doesn't have the expected shape.
 ///
 /// # Returns
 /// A result indicating success or an `ErrorParse` if a file operation fails.
@@ -383,42 +444,88 @@ pub fn md_to_html_file(md_path: &Path, html_path: &Path) -> Result<(), ErrorPars
         markdown_content.push('\n');
     }
 
-    let html_lines = str_to_html(&markdown_content)?;
+    let document = parse_document(&markdown_content)?;
 
-    let mut output_file = OpenOptions::new()
+    let output_file = OpenOptions::new()
         .create(true)
         .write(true)
         .truncate(true)
         .open(html_path)
         .map_err(ErrorParse::FileError)?;
+    let mut writer = BufWriter::new(output_file);
 
-    for line in html_lines {
-        output_file
-            .write_all(line.as_bytes())
-            .map_err(ErrorParse::FileError)?;
-        output_file
-            .write_all(b"\n")
-            .map_err(ErrorParse::FileError)?;
-    }
+    write_html(
+        &document,
+        &HtmlOptions::new(),
+        &mut HtmlRenderer,
+        &mut writer,
+    )
+    .map_err(ErrorParse::FileError)?;
+    writer.flush().map_err(ErrorParse::FileError)?;
 
     Ok(())
 }
 
-/// Converts the given markdown `text` to HTML and prints it to the console.
+/// Formats the markdown file at `md_path` into its canonical form with [`format_markdown`].
+///
+/// # Arguments
+/// * `md_path` - The path to the markdown file.
+/// * `check` - If `true`, the file is only checked and never rewritten.
+///
+/// # Behavior
+/// - If the formatted text differs from the content of the file and `check` is `false`, the file is overwritten.
+/// - An already formatted file is never rewritten.
+///
+/// # Errors
+/// - Returns an `ErrorParse::FileError` if there's an error while file r/w operation.
+/// - Malformed markdown doesn't fail, it's kept as literal text. An `ErrorParse::ParsingError` is returned only on an internal error, if the parse tree doesn't have the expected shape.
+///
+/// # Returns
+/// A result containing a tuple of whether the file is (or, with `check`, would be) changed by formatting
+/// and warnings about the parts of the file which can't be formatted and are kept unchanged.
+pub fn format_md_file(
+    md_path: &Path,
+    check: bool,
+) -> Result<(bool, Vec<ParseWarning>), ErrorParse> {
+    let markdown_content = fs::read_to_string(md_path).map_err(ErrorParse::FileError)?;
+    let (formatted, warnings) = format_markdown_with_warnings(&markdown_content)?;
+
+    if formatted == markdown_content {
+        return Ok((false, warnings));
+    }
+    if !check {
+        fs::write(md_path, formatted).map_err(ErrorParse::FileError)?;
+    }
+
+    Ok((true, warnings))
+}
+
+/// Converts the given markdown `text` to HTML and prints it to the console as it's rendered.
+/// Warnings about the document are printed to the standard error.
 ///
 /// # Arguments
 /// * `text` - A string containing the markdown content.
 ///
 /// # Errors
-/// - Returns an `ErrorParse::ParsingError` if there's an error during a parsing process.
+/// - Malformed markdown doesn't fail, it's kept as literal text. An `ErrorParse::ParsingError` is returned only on an internal error, if the parse tree doesn't have the expected shape.
+/// - Returns an `ErrorParse::FileError` if writing to the standard output fails.
 ///
 /// # Returns
-/// A result indicating success or an `ErrorParse` if the parsing fails.
+/// A result indicating success or an `ErrorParse` if writing to the standard output fails.
 pub fn parse_to_console(text: &str) -> Result<(), ErrorParse> {
-    let res = str_to_html(text)?;
-
-    for line in res {
-        println!("{}", line);
+    let (document, warnings) = parse_document_with_warnings(text)?;
+
+    let mut stdout = io::stdout().lock();
+    write_html(
+        &document,
+        &HtmlOptions::new(),
+        &mut HtmlRenderer,
+        &mut stdout,
+    )?;
+    stdout.flush()?;
+
+    for warning in warnings {
+        eprintln!("Warning: {}", warning);
     }
 
     Ok(())
@@ -1,8 +1,19 @@
-use clap::{Arg, Command};
-use rins_markdown_parser::{md_to_html_file, parse_to_console, ErrorParse};
-use std::path::PathBuf;
+use clap::ArgMatches;
+use clap::{Arg, ArgAction, Command};
+use rins_markdown_parser::{
+    format_md_file, md_to_html_file, parse_to_console, str_to_latex, str_to_man, str_to_terminal,
+    str_to_text, ErrorParse, TerminalOptions,
+};
+use std::{fs, path::PathBuf};
 
-fn main() -> Result<(), ErrorParse> {
+fn main() {
+    if let Err(err) = run() {
+        eprintln!("{}", err);
+        std::process::exit(1);
+    }
+}
+
+fn run() -> Result<(), ErrorParse> {
     let matches = Command::new("rins_markdown_parser")
         .version("0.1.0")
         .author("r-rin")
@@ -30,6 +41,55 @@ fn main() -> Result<(), ErrorParse> {
                         .conflicts_with("input_file")
                         .conflicts_with("output_file")
                 )
+                .arg(
+                    Arg::new("format")
+                        .short('f')
+                        .long("format")
+                        .value_parser(["html", "text", "latex", "man"])
+                        .default_value("html")
+                        .help("Defines the output format: html, text without any markup, latex, or man for a man(7) page")
+                )
+        )
+        .subcommand(
+            Command::new("fmt")
+                .about("Formats markdown files into their canonical form")
+                .arg(
+                    Arg::new("files")
+                        .required(true)
+                        .num_args(1..)
+                        .help("Specifies the markdown files which will be formatted in place")
+                )
+                .arg(
+                    Arg::new("check")
+                        .long("check")
+                        .action(ArgAction::SetTrue)
+                        .help("Doesn't change the files, exits with a non-zero status if any of them would be changed")
+                )
+        )
+        .subcommand(
+            Command::new("view")
+                .about("Displays provided markdown text in the terminal with colors and styles")
+                .arg(
+                    Arg::new("input_file")
+                        .short('I')
+                        .long("in")
+                        .help("Specifies the location of the file from which the markdown text will be read")
+                        .required_unless_present("text")
+                )
+                .arg(
+                    Arg::new("text")
+                        .short('t')
+                        .long("text")
+                        .help("Accepts text in markdown format from the console")
+                        .conflicts_with("input_file")
+                )
+                .arg(
+                    Arg::new("color")
+                        .long("color")
+                        .value_parser(["auto", "always", "never"])
+                        .default_value("auto")
+                        .help("Defines when colors and hyperlinks are used: auto (only if the output is a terminal and NO_COLOR isn't set), always or never")
+                )
         )
         .subcommand(
             Command::new("credits")
@@ -43,6 +103,8 @@ Usage: rins_markdown_parser [COMMAND]
 
 Commands:
   parse    Parses provided markdown text and returns it in html format
+  fmt      Formats markdown files into their canonical form
+  view     Displays provided markdown text in the terminal with colors and styles
   credits  Displays credits and project information
   help     Print this message or the help of the given subcommand(s)
 
@@ -54,6 +116,13 @@ Options:
 
     match matches.subcommand() {
         Some(("parse", sub_m)) => {
+            match sub_m.get_one::<String>("format").map(String::as_str) {
+                Some("text") => return render_markdown(sub_m, str_to_text),
+                Some("latex") => return render_markdown(sub_m, str_to_latex),
+                Some("man") => return render_markdown(sub_m, str_to_man),
+                _ => {}
+            }
+
             if let Some(text) = sub_m.get_one::<String>("text") {
                 parse_to_console(text)?;
                 return Ok(());
@@ -71,6 +140,62 @@ Options:
 
             md_to_html_file(&input_path, &output_path)?;
         }
+        Some(("fmt", sub_m)) => {
+            let check = sub_m.get_flag("check");
+            let mut unformatted = 0;
+            let mut partially_formatted = 0;
+
+            for path_string in sub_m.get_many::<String>("files").into_iter().flatten() {
+                let (changed, warnings) = format_md_file(&PathBuf::from(path_string), check)?;
+                if changed {
+                    unformatted += 1;
+                    match check {
+                        true => println!("Would reformat {}", path_string),
+                        false => println!("Reformatted {}", path_string),
+                    }
+                }
+                if !warnings.is_empty() {
+                    partially_formatted += 1;
+                }
+                for warning in warnings {
+                    eprintln!("Warning: {}: {}", path_string, warning);
+                }
+            }
+
+            if check && (unformatted > 0 || partially_formatted > 0) {
+                if unformatted > 0 {
+                    eprintln!("{} file(s) would be reformatted", unformatted);
+                }
+                if partially_formatted > 0 {
+                    eprintln!("{} file(s) can't be fully formatted", partially_formatted);
+                }
+                std::process::exit(1);
+            }
+        }
+        Some(("view", sub_m)) => {
+            let text = match sub_m.get_one::<String>("text") {
+                Some(text) => text.clone(),
+                None => fs::read_to_string(
+                    sub_m
+                        .get_one::<String>("input_file")
+                        .expect("If text is absent, then input_file is required"),
+                )?,
+            };
+
+            let mut options = TerminalOptions::from_env();
+            match sub_m.get_one::<String>("color").map(String::as_str) {
+                Some("always") => options = options.color(true).hyperlinks(true),
+                Some("never") => options = options.color(false).hyperlinks(false),
+                _ => {}
+            }
+            if std::env::var_os("COLUMNS").is_none() {
+                if let Some((terminal_size::Width(width), _)) = terminal_size::terminal_size() {
+                    options = options.width(usize::from(width));
+                }
+            }
+
+            print!("{}", str_to_terminal(&text, &options)?);
+        }
         Some(("credits", _)) => {
             println!("CREDITS\n\nThis parser was developed as part of the Rust Programming Language course at NaUKMA with the support of the Ukrainian Rust community.\nGrammar is far from an ideal one, use with caution.")
         }
@@ -79,3 +204,27 @@ Options:
 
     Ok(())
 }
+
+/// Converts the markdown text of the `parse` subcommand with the `render` function.
+/// The result is printed to the console for `--text`, or saved to the `--out` file for `--in`.
+fn render_markdown(
+    sub_m: &ArgMatches,
+    render: fn(&str) -> Result<String, ErrorParse>,
+) -> Result<(), ErrorParse> {
+    if let Some(text) = sub_m.get_one::<String>("text") {
+        print!("{}", render(text)?);
+        return Ok(());
+    }
+
+    let input_path_string = sub_m
+        .get_one::<String>("input_file")
+        .expect("If text is absent, then input_file is required");
+    let output_path_string = sub_m
+        .get_one::<String>("output_file")
+        .expect("If text is absent, then output_file is required");
+
+    let markdown_content = fs::read_to_string(input_path_string)?;
+    fs::write(output_path_string, render(&markdown_content)?)?;
+
+    Ok(())
+}
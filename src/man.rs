@@ -0,0 +1,490 @@
+use crate::ast::{Alignment, Block, BlockKind, Document, Inline, InlineKind, List, TableCell};
+
+/// Options of `man(7)` rendering.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ManOptions {
+    section: String,
+    date: Option<String>,
+    source: Option<String>,
+    manual: Option<String>,
+}
+
+impl Default for ManOptions {
+    fn default() -> Self {
+        ManOptions {
+            section: String::from("1"),
+            date: None,
+            source: None,
+            manual: None,
+        }
+    }
+}
+
+impl ManOptions {
+    /// Creates the default options: a page of the section `1` without a date, a source and a manual.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the section of the manual page, which is used if the title heading doesn't specify it, e.g. `tool(8)`.
+    pub fn section(mut self, section: impl Into<String>) -> Self {
+        self.section = section.into();
+        self
+    }
+
+    /// Sets the date of the last change of the page, written in the `.TH` line.
+    pub fn date(mut self, date: impl Into<String>) -> Self {
+        self.date = Some(date.into());
+        self
+    }
+
+    /// Sets the source of the page, e.g. the name and the version of the program, written in the `.TH` line.
+    pub fn source(mut self, source: impl Into<String>) -> Self {
+        self.source = Some(source.into());
+        self
+    }
+
+    /// Sets the title of the manual, e.g. `User Commands`, written in the `.TH` line.
+    pub fn manual(mut self, manual: impl Into<String>) -> Self {
+        self.manual = Some(manual.into());
+        self
+    }
+}
+
+/// The font of text, selected with the `\f` escape sequence.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+struct Font {
+    bold: bool,
+    italic: bool,
+}
+
+impl Font {
+    /// Returns the escape sequence which selects the font.
+    fn escape(&self) -> &'static str {
+        match (self.bold, self.italic) {
+            (false, false) => "\\fR",
+            (true, false) => "\\fB",
+            (false, true) => "\\fI",
+            (true, true) => "\\f(BI",
+        }
+    }
+}
+
+/// Renders the `document` tree to a `man(7)` page, see [`to_man_with_options`].
+///
+/// # Arguments
+/// * `document` - The document tree to render.
+///
+/// # Returns
+/// The roff source of the page.
+pub fn to_man(document: &Document) -> String {
+    to_man_with_options(document, &ManOptions::default())
+}
+
+/// Renders the `document` tree to a `man(7)` page with the given `options`.
+///
+/// The first heading becomes the `.TH` title line. If it's written as `name(section) - description`,
+/// the section is taken from the heading and a `NAME` section with the description is added.
+/// Other headings of levels 1 and 2 become `.SH` sections, deeper headings become `.SS` subsections.
+/// Bold text and code are written with `\fB`, italic and underlined text with `\fI`, code blocks are
+/// written without filling between `.nf` and `.fi`, and quotes are indented with `.RS` and `.RE`.
+/// Tables require the `tbl` preprocessor, which is requested in the first line of the page.
+///
+/// Backslashes and dashes are escaped as `\e` and `\-`, and lines which start with a dot or an apostrophe
+/// are escaped with `\&`, so the text is never interpreted as a request.
+///
+/// # Arguments
+/// * `document` - The document tree to render.
+/// * `options` - Options of the rendering.
+///
+/// # Returns
+/// The roff source of the page.
+pub fn to_man_with_options(document: &Document, options: &ManOptions) -> String {
+    let mut lines = Vec::new();
+    if contains_table(&document.blocks) {
+        lines.push(String::from("'\\\" t"));
+    }
+
+    let title = document
+        .blocks
+        .iter()
+        .position(|block| matches!(block.kind, BlockKind::Heading { .. }));
+    if let Some(BlockKind::Heading { children, .. }) =
+        title.map(|index| &document.blocks[index].kind)
+    {
+        lines.extend(render_title(&plain_text(children), options));
+    }
+
+    for (index, block) in document.blocks.iter().enumerate() {
+        if Some(index) != title {
+            lines.extend(render_block(block));
+        }
+    }
+
+    lines.into_iter().map(|line| line + "\n").collect()
+}
+
+/// Returns whether there's a table among the blocks, including tables inside quotes and list items.
+///
+/// # Arguments
+/// * `blocks` - The blocks.
+///
+/// # Returns
+/// `true` if the page requires the `tbl` preprocessor.
+fn contains_table(blocks: &[Block]) -> bool {
+    blocks.iter().any(|block| match &block.kind {
+        BlockKind::Table { .. } => true,
+        BlockKind::Quote { children } => contains_table(children),
+        BlockKind::List(list) => list_contains_table(list),
+        _ => false,
+    })
+}
+
+/// Returns whether the blocks of the items of a `list` or its nested lists contain a table.
+fn list_contains_table(list: &List) -> bool {
+    list.items.iter().any(|item| {
+        item.nested.as_ref().is_some_and(list_contains_table) || contains_table(&item.blocks)
+    })
+}
+
+/// Renders the `.TH` title line from the text of the title heading.
+///
+/// # Arguments
+/// * `text` - The plain text of the title heading, e.g. `tool(1) - does things`.
+/// * `options` - Options of the rendering.
+///
+/// # Returns
+/// Lines of the title, followed by a `NAME` section if the heading has a description.
+fn render_title(text: &str, options: &ManOptions) -> Vec<String> {
+    let (name, description) = match text.split_once(" - ").or_else(|| text.split_once(" -- ")) {
+        Some((name, description)) => (name.trim(), Some(description.trim())),
+        None => (text.trim(), None),
+    };
+    let (name, section) = match name.strip_suffix(')').and_then(|name| name.split_once('(')) {
+        Some((name, section)) if !name.is_empty() && !section.is_empty() => (name, section),
+        _ => (name, options.section.as_str()),
+    };
+
+    let mut fields = vec![name.to_uppercase(), section.to_string()];
+    let optional = [&options.date, &options.source, &options.manual];
+    if let Some(last) = optional.iter().rposition(|field| field.is_some()) {
+        fields.extend(
+            optional[..=last]
+                .iter()
+                .map(|field| field.as_deref().unwrap_or_default().to_string()),
+        );
+    }
+
+    let mut lines = vec![format!(
+        ".TH {}",
+        fields
+            .iter()
+            .map(|field| format!("\"{}\"", escape_roff(field).replace('"', "\\(dq")))
+            .collect::<Vec<_>>()
+            .join(" ")
+    )];
+    if let Some(description) = description {
+        lines.push(String::from(".SH NAME"));
+        lines.push(escape_line_start(&format!(
+            "{} \\- {}",
+            escape_roff(name),
+            escape_roff(description)
+        )));
+    }
+
+    lines
+}
+
+/// Renders a single block.
+///
+/// # Arguments
+/// * `block` - The block.
+///
+/// # Returns
+/// Lines of the block, empty if the block isn't rendered.
+fn render_block(block: &Block) -> Vec<String> {
+    match &block.kind {
+        BlockKind::Heading { level, children } => {
+            let request = if *level <= 2 { ".SH" } else { ".SS" };
+            // Headings are bold, so styled text in a heading switches back to bold.
+            let font = Font {
+                bold: true,
+                ..Font::default()
+            };
+            let text = render_inlines(children, font).replace('\n', " ");
+            vec![format!("{} {}", request, text.replace('"', "\\(dq"))]
+        }
+        BlockKind::Paragraph { children } => {
+            let mut lines = vec![String::from(".PP")];
+            lines.extend(render_text(children));
+            lines
+        }
+        BlockKind::CodeBlock { content, .. } => {
+            let mut lines = vec![
+                String::from(".PP"),
+                String::from(".RS 4"),
+                String::from(".nf"),
+            ];
+            lines.extend(
+                content
+                    .lines()
+                    .map(|line| escape_line_start(&escape_roff(line))),
+            );
+            lines.push(String::from(".fi"));
+            lines.push(String::from(".RE"));
+            lines
+        }
+        BlockKind::Quote { children } => {
+            let mut lines = vec![String::from(".RS")];
+            lines.extend(children.iter().flat_map(render_block));
+            lines.push(String::from(".RE"));
+            lines
+        }
+        BlockKind::List(list) => render_list(list),
+        BlockKind::Table {
+            alignments,
+            header,
+            rows,
+        } => render_table(alignments, header, rows),
+        BlockKind::HorizontalRule => {
+            vec![String::from(".PP"), String::from("   *   *   *   *   *")]
+        }
+        BlockKind::FootnoteDefinition { label, children } => {
+            let mut lines = vec![format!(
+                ".IP \"[{}]\" 4",
+                escape_roff(label).replace('"', "\\(dq")
+            )];
+            lines.extend(render_text(children));
+            lines
+        }
+        BlockKind::EmptyLine | BlockKind::LinkDefinition { .. } => Vec::new(),
+    }
+}
+
+/// Renders a list as indented paragraphs with a bullet or a number.
+/// Nested lists and blocks of the items are indented with `.RS` and `.RE`.
+///
+/// # Arguments
+/// * `list` - The list.
+///
+/// # Returns
+/// Lines of the list.
+fn render_list(list: &List) -> Vec<String> {
+    let mut lines = Vec::new();
+
+    for (i, item) in list.items.iter().enumerate() {
+        match list.ordered {
+            true => lines.push(format!(".IP {}. 4", list.start + i as u64)),
+            false => lines.push(String::from(".IP \\(bu 2")),
+        }
+
+        let mut text = render_text(&item.children);
+        if let Some(checked) = item.checked {
+            let checkbox = if checked { "[x] " } else { "[ ] " };
+            match text.first_mut() {
+                Some(first) => first.insert_str(0, checkbox),
+                None => text.push(checkbox.trim_end().to_string()),
+            }
+        }
+        lines.extend(text);
+
+        if let Some(nested) = &item.nested {
+            lines.push(String::from(".RS"));
+            lines.extend(render_list(nested));
+            lines.push(String::from(".RE"));
+        }
+        if !item.blocks.is_empty() {
+            lines.push(String::from(".RS"));
+            lines.extend(item.blocks.iter().flat_map(render_block));
+            lines.push(String::from(".RE"));
+        }
+    }
+
+    lines
+}
+
+/// Renders a table for the `tbl` preprocessor, with a bold header row and a line under it.
+///
+/// # Arguments
+/// * `alignments` - Alignments of the columns.
+/// * `header` - Cells of the header row.
+/// * `rows` - Cells of the body rows.
+///
+/// # Returns
+/// Lines of the table.
+fn render_table(
+    alignments: &[Alignment],
+    header: &[TableCell],
+    rows: &[Vec<TableCell>],
+) -> Vec<String> {
+    let columns = std::iter::once(header.len())
+        .chain(rows.iter().map(Vec::len))
+        .chain(std::iter::once(alignments.len()))
+        .max()
+        .unwrap_or_default();
+    let format = (0..columns)
+        .map(|column| match alignments.get(column) {
+            Some(Alignment::Center) => "c",
+            Some(Alignment::Right) => "r",
+            _ => "l",
+        })
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    let bold = Font {
+        bold: true,
+        ..Font::default()
+    };
+    let render_row = |row: &[TableCell], font: Font| {
+        row.iter()
+            .map(|cell| {
+                let text = render_inlines(&cell.children, font).replace(['\n', '\t'], " ");
+                let text = match font == Font::default() {
+                    true => text,
+                    false => format!("{}{}\\fR", font.escape(), text),
+                };
+                match text.starts_with(['.', '\'', '_', '=']) {
+                    true => format!("\\&{}", text),
+                    false => text,
+                }
+            })
+            .collect::<Vec<_>>()
+            .join("\t")
+    };
+
+    let mut lines = vec![
+        String::from(".TS"),
+        format!("{}.", format),
+        render_row(header, bold),
+        String::from("_"),
+    ];
+    lines.extend(rows.iter().map(|row| render_row(row, Font::default())));
+    lines.push(String::from(".TE"));
+
+    lines
+}
+
+/// Renders inline elements as lines of filled text. Leading whitespace of the lines is removed,
+/// since it would break the line.
+///
+/// # Arguments
+/// * `inlines` - The inline elements.
+///
+/// # Returns
+/// Lines of the text.
+fn render_text(inlines: &[Inline]) -> Vec<String> {
+    render_inlines(inlines, Font::default())
+        .split('\n')
+        .map(|line| escape_line_start(line.trim_start()))
+        .filter(|line| !line.is_empty())
+        .collect()
+}
+
+/// Renders inline elements. Line breaks between lines of a paragraph are kept.
+///
+/// # Arguments
+/// * `inlines` - The inline elements.
+/// * `font` - The font of the element which contains the inline elements.
+///
+/// # Returns
+/// The roff source of the elements.
+fn render_inlines(inlines: &[Inline], font: Font) -> String {
+    let mut roff = String::new();
+
+    for inline in inlines {
+        match &inline.kind {
+            InlineKind::Text(text) => roff.push_str(&escape_roff(text)),
+            InlineKind::Code(code) => roff.push_str(&styled(
+                &escape_roff(code),
+                Font { bold: true, ..font },
+                font,
+            )),
+            InlineKind::Strong(children) => {
+                let inner = Font { bold: true, ..font };
+                roff.push_str(&styled(&render_inlines(children, inner), inner, font))
+            }
+            InlineKind::Emphasis(children) | InlineKind::Underline(children) => {
+                let inner = Font {
+                    italic: true,
+                    ..font
+                };
+                roff.push_str(&styled(&render_inlines(children, inner), inner, font))
+            }
+            InlineKind::Strikethrough(children) => roff.push_str(&render_inlines(children, font)),
+            InlineKind::Link { url, children, .. } => {
+                let text = render_inlines(children, font);
+                let url = escape_roff(url);
+                match text == url {
+                    true => roff.push_str(&format!("<{}>", url)),
+                    false => roff.push_str(&format!("{} <{}>", text, url)),
+                }
+            }
+            InlineKind::Image { alt, .. } => roff.push_str(&escape_roff(alt)),
+            InlineKind::FootnoteReference { label } => {
+                roff.push_str(&format!("[{}]", escape_roff(label)))
+            }
+            InlineKind::LineBreak => roff.push('\n'),
+        }
+    }
+
+    roff
+}
+
+/// Writes `text` in the `inner` font, then switches back to the `outer` font.
+fn styled(text: &str, inner: Font, outer: Font) -> String {
+    match inner == outer {
+        true => text.to_string(),
+        false => format!("{}{}{}", inner.escape(), text, outer.escape()),
+    }
+}
+
+/// Returns the plain text of inline elements, used in the title line.
+fn plain_text(inlines: &[Inline]) -> String {
+    let mut text = String::new();
+
+    for inline in inlines {
+        match &inline.kind {
+            InlineKind::Text(value) | InlineKind::Code(value) => text.push_str(value),
+            InlineKind::Strong(children)
+            | InlineKind::Emphasis(children)
+            | InlineKind::Strikethrough(children)
+            | InlineKind::Underline(children)
+            | InlineKind::Link { children, .. } => text.push_str(&plain_text(children)),
+            InlineKind::Image { alt, .. } => text.push_str(alt),
+            InlineKind::FootnoteReference { .. } => {}
+            InlineKind::LineBreak => text.push(' '),
+        }
+    }
+
+    text
+}
+
+/// Escapes backslashes and dashes of text for roff: a backslash is written as `\e`,
+/// and a dash as `\-`, so it's printed as a hyphen-minus which can be copied into a shell.
+///
+/// # Arguments
+/// * `text` - The text.
+///
+/// # Returns
+/// The escaped text.
+pub fn escape_roff(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+
+    for c in text.chars() {
+        match c {
+            '\\' => escaped.push_str("\\e"),
+            '-' => escaped.push_str("\\-"),
+            c => escaped.push(c),
+        }
+    }
+
+    escaped
+}
+
+/// Escapes a line which starts with a dot or an apostrophe with `\&`, so it isn't interpreted as a request.
+fn escape_line_start(line: &str) -> String {
+    match line.starts_with(['.', '\'']) {
+        true => format!("\\&{}", line),
+        false => line.to_string(),
+    }
+}
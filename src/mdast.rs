@@ -0,0 +1,366 @@
+use serde_json::{json, Map, Value};
+
+use crate::{
+    ast::normalize_label, parse_document, Alignment, Block, BlockKind, Document, ErrorParse,
+    Inline, InlineKind, List, ListItem, Span, TableCell,
+};
+
+/// Parses provided `text` in markdown format and converts its document tree
+/// to the JSON of an [mdast](https://github.com/syntax-tree/mdast) `root` node.
+/// Available only with the `serde` feature.
+///
+/// # Arguments
+/// * `text` - A string slice containing the markdown content.
+///
+/// # Errors
+/// - Returns an `ErrorParse::ParsingError` on an internal error of parsing, if the parse tree doesn't have the expected shape.
+/// - Returns an `ErrorParse::JsonError` if the document can't be serialized.
+///
+/// # Returns
+/// A result containing either the mdast JSON string or a `ErrorParse` if the serialization fails.
+pub fn str_to_mdast(text: &str) -> Result<String, ErrorParse> {
+    document_to_mdast(&parse_document(text)?)
+}
+
+/// Converts the `document` tree to the JSON of an mdast `root` node, which can be used by unified/remark tools.
+/// Available only with the `serde` feature.
+///
+/// Every node has a `position` with 1-based `line` and `column`, and 0-based byte `offset`.
+/// Underlined text has no mdast node, so its children are inserted into its parent.
+/// Empty lines are dropped, and lines of a paragraph are joined into `text` nodes with `\n`.
+///
+/// # Arguments
+/// * `document` - The document tree to convert.
+///
+/// # Errors
+/// - Returns an `ErrorParse::JsonError` if the document can't be serialized.
+///
+/// # Returns
+/// A result containing either the mdast JSON string or a `ErrorParse` if the serialization fails.
+pub fn document_to_mdast(document: &Document) -> Result<String, ErrorParse> {
+    let root = node(
+        "root",
+        document.span,
+        json!({ "children": blocks_to_mdast(&document.blocks) }),
+    );
+
+    serde_json::to_string(&root)
+        .map_err(|err| ErrorParse::JsonError(format!("Failed to serialize a document: {}", err)))
+}
+
+/// Creates an mdast node with its `type`, the `fields` of the node and its `position`.
+///
+/// # Arguments
+/// * `kind` - The type of the node.
+/// * `span` - The range of the node in the markdown input.
+/// * `fields` - A JSON object with other fields of the node.
+///
+/// # Returns
+/// A JSON object of the node.
+fn node(kind: &str, span: Span, fields: Value) -> Value {
+    let mut object = Map::new();
+    object.insert(String::from("type"), json!(kind));
+    if let Value::Object(fields) = fields {
+        object.extend(fields);
+    }
+    object.insert(String::from("position"), position(span));
+    Value::Object(object)
+}
+
+/// Converts a span to an mdast `position` object.
+///
+/// # Arguments
+/// * `span` - The range of a node.
+///
+/// # Returns
+/// A JSON object with `start` and `end` points.
+fn position(span: Span) -> Value {
+    json!({
+        "start": { "line": span.start.line, "column": span.start.column, "offset": span.start.offset },
+        "end": { "line": span.end.line, "column": span.end.column, "offset": span.end.offset },
+    })
+}
+
+/// Returns the range from the start of the first span to the end of the last one.
+///
+/// # Arguments
+/// * `spans` - Spans of nodes in the order of the document.
+///
+/// # Returns
+/// The range which covers all spans, or `None` if there are no spans.
+fn covering_span(mut spans: impl DoubleEndedIterator<Item = Span> + Clone) -> Option<Span> {
+    let start = spans.clone().next()?.start;
+    let end = spans.next_back()?.end;
+    Some(Span { start, end })
+}
+
+/// Converts blocks to mdast nodes, empty lines are dropped.
+///
+/// # Arguments
+/// * `blocks` - Blocks of the document or a blockquote.
+///
+/// # Returns
+/// The mdast nodes of the blocks.
+fn blocks_to_mdast(blocks: &[Block]) -> Vec<Value> {
+    blocks.iter().filter_map(block_to_mdast).collect()
+}
+
+/// Converts a block to an mdast node.
+///
+/// # Arguments
+/// * `block` - The block.
+///
+/// # Returns
+/// The mdast node of the block, or `None` if the block has no mdast node.
+fn block_to_mdast(block: &Block) -> Option<Value> {
+    let value = match &block.kind {
+        BlockKind::Heading { level, children } => node(
+            "heading",
+            block.span,
+            json!({ "depth": level, "children": inlines_to_mdast(children) }),
+        ),
+        BlockKind::Paragraph { children } => node(
+            "paragraph",
+            block.span,
+            json!({ "children": inlines_to_mdast(children) }),
+        ),
+        BlockKind::CodeBlock { lang, content } => node(
+            "code",
+            block.span,
+            json!({ "lang": lang, "meta": null, "value": content }),
+        ),
+        BlockKind::Quote { children } => node(
+            "blockquote",
+            block.span,
+            json!({ "children": blocks_to_mdast(children) }),
+        ),
+        BlockKind::List(list) => list_to_mdast(list),
+        BlockKind::Table {
+            alignments,
+            header,
+            rows,
+        } => {
+            let align: Vec<Value> = alignments
+                .iter()
+                .map(|alignment| match alignment {
+                    Alignment::None => Value::Null,
+                    Alignment::Left => json!("left"),
+                    Alignment::Center => json!("center"),
+                    Alignment::Right => json!("right"),
+                })
+                .collect();
+            let children: Vec<Value> = std::iter::once(header)
+                .chain(rows)
+                .map(|row| row_to_mdast(row))
+                .collect();
+            node(
+                "table",
+                block.span,
+                json!({ "align": align, "children": children }),
+            )
+        }
+        BlockKind::HorizontalRule => node("thematicBreak", block.span, json!({})),
+        BlockKind::EmptyLine => return None,
+        BlockKind::FootnoteDefinition { label, children } => {
+            let paragraph = covering_span(children.iter().map(|inline| inline.span)).map(|span| {
+                node(
+                    "paragraph",
+                    span,
+                    json!({ "children": inlines_to_mdast(children) }),
+                )
+            });
+            node(
+                "footnoteDefinition",
+                block.span,
+                json!({ "identifier": normalize_label(label), "label": label, "children": paragraph.into_iter().collect::<Vec<_>>() }),
+            )
+        }
+        BlockKind::LinkDefinition { label, url, title } => node(
+            "definition",
+            block.span,
+            json!({ "identifier": normalize_label(label), "label": label, "url": url, "title": title }),
+        ),
+    };
+
+    Some(value)
+}
+
+/// Converts a list to an mdast `list` node.
+///
+/// # Arguments
+/// * `list` - The list.
+///
+/// # Returns
+/// The mdast node of the list.
+fn list_to_mdast(list: &List) -> Value {
+    let items: Vec<Value> = list.items.iter().map(item_to_mdast).collect();
+    node(
+        "list",
+        list.span,
+        json!({
+            "ordered": list.ordered,
+            "start": if list.ordered { json!(list.start) } else { Value::Null },
+            "spread": false,
+            "children": items,
+        }),
+    )
+}
+
+/// Converts a list item to an mdast `listItem` node, its text is wrapped in a paragraph and followed by its blocks.
+///
+/// # Arguments
+/// * `item` - The list item.
+///
+/// # Returns
+/// The mdast node of the list item.
+fn item_to_mdast(item: &ListItem) -> Value {
+    let mut children = Vec::new();
+    if let Some(span) = covering_span(item.children.iter().map(|inline| inline.span)) {
+        children.push(node(
+            "paragraph",
+            span,
+            json!({ "children": inlines_to_mdast(&item.children) }),
+        ));
+    }
+    if let Some(nested) = &item.nested {
+        children.push(list_to_mdast(nested));
+    }
+    children.extend(blocks_to_mdast(&item.blocks));
+
+    // An item is spread if its children are separated by an empty line.
+    let spans: Vec<Span> = covering_span(item.children.iter().map(|inline| inline.span))
+        .into_iter()
+        .chain(item.nested.iter().map(|nested| nested.span))
+        .chain(
+            item.blocks
+                .iter()
+                .filter(|block| block.kind != BlockKind::EmptyLine)
+                .map(|block| block.span),
+        )
+        .collect();
+    let spread = spans
+        .windows(2)
+        .any(|pair| pair[1].start.line > pair[0].end.line + 1);
+
+    node(
+        "listItem",
+        item.span,
+        json!({ "checked": item.checked, "spread": spread, "children": children }),
+    )
+}
+
+/// Converts a row of a table to an mdast `tableRow` node.
+///
+/// # Arguments
+/// * `row` - Cells of the row.
+///
+/// # Returns
+/// The mdast node of the row.
+fn row_to_mdast(row: &[TableCell]) -> Value {
+    let cells: Vec<Value> = row
+        .iter()
+        .map(|cell| {
+            node(
+                "tableCell",
+                cell.span,
+                json!({ "children": inlines_to_mdast(&cell.children) }),
+            )
+        })
+        .collect();
+    let span = covering_span(row.iter().map(|cell| cell.span)).unwrap_or_default();
+
+    node("tableRow", span, json!({ "children": cells }))
+}
+
+/// Converts inline elements to mdast nodes. Adjacent text and line breaks are merged into one `text` node.
+///
+/// # Arguments
+/// * `inlines` - Inline elements of a block.
+///
+/// # Returns
+/// The mdast nodes of the inline elements.
+fn inlines_to_mdast(inlines: &[Inline]) -> Vec<Value> {
+    let mut nodes = Vec::new();
+    push_inlines(inlines, &mut nodes);
+    nodes
+}
+
+/// Appends mdast nodes of inline elements to `nodes`.
+///
+/// # Arguments
+/// * `inlines` - Inline elements.
+/// * `nodes` - Already converted siblings of the elements.
+fn push_inlines(inlines: &[Inline], nodes: &mut Vec<Value>) {
+    for inline in inlines {
+        let value = match &inline.kind {
+            InlineKind::Text(text) => {
+                push_text(text, inline.span, nodes);
+                continue;
+            }
+            InlineKind::LineBreak => {
+                push_text("\n", inline.span, nodes);
+                continue;
+            }
+            InlineKind::Underline(children) => {
+                push_inlines(children, nodes);
+                continue;
+            }
+            InlineKind::Code(code) => node("inlineCode", inline.span, json!({ "value": code })),
+            InlineKind::Strong(children) => node(
+                "strong",
+                inline.span,
+                json!({ "children": inlines_to_mdast(children) }),
+            ),
+            InlineKind::Emphasis(children) => node(
+                "emphasis",
+                inline.span,
+                json!({ "children": inlines_to_mdast(children) }),
+            ),
+            InlineKind::Strikethrough(children) => node(
+                "delete",
+                inline.span,
+                json!({ "children": inlines_to_mdast(children) }),
+            ),
+            InlineKind::Link {
+                url,
+                title,
+                children,
+            } => node(
+                "link",
+                inline.span,
+                json!({ "url": url, "title": title, "children": inlines_to_mdast(children) }),
+            ),
+            InlineKind::Image { url, title, alt } => node(
+                "image",
+                inline.span,
+                json!({ "url": url, "title": title, "alt": alt }),
+            ),
+            InlineKind::FootnoteReference { label } => node(
+                "footnoteReference",
+                inline.span,
+                json!({ "identifier": normalize_label(label), "label": label }),
+            ),
+        };
+        nodes.push(value);
+    }
+}
+
+/// Appends `text` to the last node if it's a `text` node, otherwise appends a new `text` node.
+///
+/// # Arguments
+/// * `text` - The text.
+/// * `span` - The range of the text.
+/// * `nodes` - Already converted siblings of the text.
+fn push_text(text: &str, span: Span, nodes: &mut Vec<Value>) {
+    if let Some(Value::Object(last)) = nodes.last_mut() {
+        if last["type"] == "text" {
+            if let Some(Value::String(value)) = last.get_mut("value") {
+                value.push_str(text);
+            }
+            last["position"]["end"] = position(span)["end"].take();
+            return;
+        }
+    }
+
+    nodes.push(node("text", span, json!({ "value": text })));
+}
@@ -0,0 +1,175 @@
+use crate::Rule;
+
+/// Options of markdown parsing, which turn individual markdown constructs on and off.
+/// All constructs are enabled by default. A disabled construct is kept as literal text:
+/// e.g., with disabled headings `# Title` becomes a paragraph with the text `# Title`.
+/// Disabled blocks don't interrupt a paragraph, so `text\n# Title` stays a single paragraph.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ParseOptions {
+    headings: bool,
+    blockquotes: bool,
+    code_blocks: bool,
+    horizontal_rules: bool,
+    lists: bool,
+    task_lists: bool,
+    tables: bool,
+    footnotes: bool,
+    links: bool,
+    images: bool,
+    code_spans: bool,
+    underline: bool,
+    strikethrough: bool,
+    strict: bool,
+}
+
+impl Default for ParseOptions {
+    fn default() -> Self {
+        ParseOptions {
+            headings: true,
+            blockquotes: true,
+            code_blocks: true,
+            horizontal_rules: true,
+            lists: true,
+            task_lists: true,
+            tables: true,
+            footnotes: true,
+            links: true,
+            images: true,
+            code_spans: true,
+            underline: true,
+            strikethrough: true,
+            strict: false,
+        }
+    }
+}
+
+impl ParseOptions {
+    /// Creates the default options, with all constructs enabled.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets whether ATX (`# Heading`) and setext (`Heading` underlined with `===`) headings are parsed.
+    pub fn headings(mut self, enabled: bool) -> Self {
+        self.headings = enabled;
+        self
+    }
+
+    /// Sets whether blockquotes (`> Quote`) are parsed.
+    pub fn blockquotes(mut self, enabled: bool) -> Self {
+        self.blockquotes = enabled;
+        self
+    }
+
+    /// Sets whether fenced code blocks are parsed.
+    pub fn code_blocks(mut self, enabled: bool) -> Self {
+        self.code_blocks = enabled;
+        self
+    }
+
+    /// Sets whether horizontal rules (`---`) are parsed.
+    pub fn horizontal_rules(mut self, enabled: bool) -> Self {
+        self.horizontal_rules = enabled;
+        self
+    }
+
+    /// Sets whether ordered and unordered lists are parsed.
+    pub fn lists(mut self, enabled: bool) -> Self {
+        self.lists = enabled;
+        self
+    }
+
+    /// Sets whether checkboxes of task list items (`- [x] Done`) are parsed.
+    pub fn task_lists(mut self, enabled: bool) -> Self {
+        self.task_lists = enabled;
+        self
+    }
+
+    /// Sets whether tables are parsed.
+    pub fn tables(mut self, enabled: bool) -> Self {
+        self.tables = enabled;
+        self
+    }
+
+    /// Sets whether footnote definitions (`[^note]: Text`) and references (`[^note]`) are parsed.
+    pub fn footnotes(mut self, enabled: bool) -> Self {
+        self.footnotes = enabled;
+        self
+    }
+
+    /// Sets whether inline links, reference links and link reference definitions are parsed.
+    pub fn links(mut self, enabled: bool) -> Self {
+        self.links = enabled;
+        self
+    }
+
+    /// Sets whether inline and reference images are parsed.
+    pub fn images(mut self, enabled: bool) -> Self {
+        self.images = enabled;
+        self
+    }
+
+    /// Sets whether inline code spans (`` `code` ``) are parsed.
+    pub fn code_spans(mut self, enabled: bool) -> Self {
+        self.code_spans = enabled;
+        self
+    }
+
+    /// Sets whether underlined text (`__underline__`) is parsed.
+    pub fn underline(mut self, enabled: bool) -> Self {
+        self.underline = enabled;
+        self
+    }
+
+    /// Sets whether strikethrough text (`~~strikethrough~~`) is parsed.
+    pub fn strikethrough(mut self, enabled: bool) -> Self {
+        self.strikethrough = enabled;
+        self
+    }
+
+    /// Sets whether unmatched emphasis delimiters (e.g., an unclosed `**`) are reported as syntax errors
+    /// instead of being kept as literal text.
+    pub fn strict(mut self, enabled: bool) -> Self {
+        self.strict = enabled;
+        self
+    }
+
+    /// Returns whether unmatched emphasis delimiters are reported as syntax errors.
+    pub(crate) fn is_strict(&self) -> bool {
+        self.strict
+    }
+
+    /// Returns whether the markdown construct parsed by the grammar `rule` is enabled.
+    /// Rules which don't start a construct that can be turned off are always enabled.
+    ///
+    /// # Arguments
+    /// * `rule` - The grammar rule of a block or an inline element.
+    ///
+    /// # Returns
+    /// `true` if the construct is parsed, `false` if it's kept as literal text.
+    pub(crate) fn allows(&self, rule: Rule) -> bool {
+        match rule {
+            Rule::heading1
+            | Rule::heading2
+            | Rule::heading3
+            | Rule::heading4
+            | Rule::heading5
+            | Rule::heading6
+            | Rule::setext_heading1
+            | Rule::setext_heading2 => self.headings,
+            Rule::quote => self.blockquotes,
+            Rule::code_block => self.code_blocks,
+            Rule::horizontal_rule => self.horizontal_rules,
+            Rule::unordered_list | Rule::ordered_list => self.lists,
+            Rule::task_checkbox => self.task_lists,
+            Rule::table => self.tables,
+            Rule::footnote_definition | Rule::footnote_reference => self.footnotes,
+            Rule::link_definition | Rule::inline_link | Rule::reference_link => self.links,
+            Rule::inline_image | Rule::reference_image => self.images,
+            Rule::code_span => self.code_spans,
+            Rule::underline => self.underline,
+            Rule::strikethrough => self.strikethrough,
+            _ => true,
+        }
+    }
+}
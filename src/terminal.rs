@@ -0,0 +1,770 @@
+use std::io::IsTerminal;
+
+use crate::ast::{Alignment, Block, BlockKind, Document, Inline, InlineKind, List, TableCell};
+
+/// SGR colors of headings by their level, from 1 to 6: magenta, blue, cyan, green, yellow and white.
+const HEADING_COLORS: [u8; 6] = [35, 34, 36, 32, 33, 37];
+
+/// The SGR color of links (blue).
+const LINK_COLOR: u8 = 34;
+
+/// Options of rendering for a terminal.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TerminalOptions {
+    width: usize,
+    color: bool,
+    hyperlinks: bool,
+}
+
+impl Default for TerminalOptions {
+    fn default() -> Self {
+        TerminalOptions {
+            width: 80,
+            color: true,
+            hyperlinks: true,
+        }
+    }
+}
+
+impl TerminalOptions {
+    /// Creates the default options: 80 columns, with colors and hyperlinks.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates the options from the environment: colors and hyperlinks are disabled if the standard output
+    /// isn't a terminal (e.g. it's redirected to a file) or if the `NO_COLOR` variable is set to a non-empty value,
+    /// and the width is read from the `COLUMNS` variable if it's set.
+    pub fn from_env() -> Self {
+        let no_color = !std::io::stdout().is_terminal()
+            || std::env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty());
+        let width = std::env::var("COLUMNS")
+            .ok()
+            .and_then(|columns| columns.trim().parse().ok());
+
+        let options = Self::new().color(!no_color).hyperlinks(!no_color);
+        match width {
+            Some(width) => options.width(width),
+            None => options,
+        }
+    }
+
+    /// Sets the number of columns to which paragraphs, headings and list items are wrapped.
+    pub fn width(mut self, width: usize) -> Self {
+        self.width = width;
+        self
+    }
+
+    /// Sets whether text is styled with SGR escape sequences (bold, italic, colors, etc.).
+    pub fn color(mut self, enabled: bool) -> Self {
+        self.color = enabled;
+        self
+    }
+
+    /// Sets whether links are written as OSC 8 hyperlinks. Otherwise the URL of a link is written after its text.
+    pub fn hyperlinks(mut self, enabled: bool) -> Self {
+        self.hyperlinks = enabled;
+        self
+    }
+}
+
+/// The style of a piece of text, written as an SGR escape sequence.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+struct Style {
+    bold: bool,
+    dim: bool,
+    italic: bool,
+    underline: bool,
+    strikethrough: bool,
+    /// The SGR code of the foreground color.
+    color: Option<u8>,
+    /// Whether the text has the background of code.
+    code: bool,
+}
+
+impl Style {
+    /// Returns the parameters of the SGR escape sequence of the style, or an empty string for plain text.
+    fn sgr(&self) -> String {
+        let codes = [
+            (self.bold, "1"),
+            (self.dim, "2"),
+            (self.italic, "3"),
+            (self.underline, "4"),
+            (self.strikethrough, "9"),
+            (self.code, "100"),
+        ];
+        let mut parameters: Vec<String> = codes
+            .iter()
+            .filter(|(enabled, _)| *enabled)
+            .map(|(_, code)| String::from(*code))
+            .collect();
+        if let Some(color) = self.color {
+            parameters.push(color.to_string());
+        }
+
+        parameters.join(";")
+    }
+}
+
+/// A piece of text with a single style, which may be a part of a link.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Piece {
+    text: String,
+    style: Style,
+    link: Option<String>,
+}
+
+impl Piece {
+    /// Creates a piece of text which is not a part of a link.
+    fn new(text: impl Into<String>, style: Style) -> Self {
+        Piece {
+            text: text.into(),
+            style,
+            link: None,
+        }
+    }
+
+    /// Returns the number of columns taken by the piece.
+    fn width(&self) -> usize {
+        self.text.chars().count()
+    }
+}
+
+/// Renders the `document` tree for a terminal with the given `options`.
+///
+/// Headings are bold and colored by their level. Bold, italic, underlined and strikethrough text is styled with
+/// SGR escape sequences, links are OSC 8 hyperlinks. Code blocks are indented and have a background, quotes have
+/// a `│` gutter. Paragraphs, headings and list items are wrapped to the width of the options.
+/// Control characters of the markdown input are replaced, so the input can't send its own escape sequences.
+///
+/// # Arguments
+/// * `document` - The document tree to render.
+/// * `options` - Options of the rendering.
+///
+/// # Returns
+/// The text for a terminal, with a line break after every line.
+pub fn to_terminal(document: &Document, options: &TerminalOptions) -> String {
+    render_blocks(&document.blocks, options.width, options)
+        .into_iter()
+        .map(|line| line + "\n")
+        .collect()
+}
+
+/// Renders blocks, separated by an empty line. Blocks without lines are skipped.
+///
+/// # Arguments
+/// * `blocks` - The blocks.
+/// * `width` - The number of columns available to the blocks.
+/// * `options` - Options of the rendering.
+///
+/// # Returns
+/// Lines of the blocks.
+fn render_blocks(blocks: &[Block], width: usize, options: &TerminalOptions) -> Vec<String> {
+    let mut lines = Vec::new();
+
+    for block in blocks {
+        let block_lines = render_block(block, width, options);
+        if block_lines.is_empty() {
+            continue;
+        }
+        if !lines.is_empty() {
+            lines.push(String::new());
+        }
+        lines.extend(block_lines);
+    }
+
+    lines
+}
+
+/// Renders a single block.
+///
+/// # Arguments
+/// * `block` - The block.
+/// * `width` - The number of columns available to the block.
+/// * `options` - Options of the rendering.
+///
+/// # Returns
+/// Lines of the block, empty if the block isn't shown.
+fn render_block(block: &Block, width: usize, options: &TerminalOptions) -> Vec<String> {
+    match &block.kind {
+        BlockKind::Heading { level, children } => {
+            let style = Style {
+                bold: true,
+                color: Some(HEADING_COLORS[usize::from(level.clamp(&1, &6) - 1)]),
+                ..Style::default()
+            };
+            render_paragraph(&render_inlines(children, style, options), width, options)
+        }
+        BlockKind::Paragraph { children } => render_paragraph(
+            &render_inlines(children, Style::default(), options),
+            width,
+            options,
+        ),
+        BlockKind::CodeBlock { content, .. } => render_code_block(content, options),
+        BlockKind::Quote { children } => {
+            let gutter = write_piece(
+                &Piece::new(
+                    "│",
+                    Style {
+                        dim: true,
+                        ..Style::default()
+                    },
+                ),
+                options,
+            );
+            render_blocks(children, width.saturating_sub(2), options)
+                .into_iter()
+                .map(|line| match line.is_empty() {
+                    true => gutter.clone(),
+                    false => format!("{} {}", gutter, line),
+                })
+                .collect()
+        }
+        BlockKind::List(list) => render_list(list, width, options),
+        BlockKind::Table {
+            alignments,
+            header,
+            rows,
+        } => render_table(alignments, header, rows, options),
+        BlockKind::HorizontalRule => vec![write_piece(
+            &Piece::new(
+                "─".repeat(width),
+                Style {
+                    dim: true,
+                    ..Style::default()
+                },
+            ),
+            options,
+        )],
+        BlockKind::FootnoteDefinition { label, children } => {
+            let mut pieces = vec![Piece::new(
+                format!("[{}] ", sanitize(label)),
+                Style {
+                    dim: true,
+                    ..Style::default()
+                },
+            )];
+            pieces.extend(render_inlines(children, Style::default(), options));
+            render_paragraph(&pieces, width, options)
+        }
+        BlockKind::EmptyLine | BlockKind::LinkDefinition { .. } => Vec::new(),
+    }
+}
+
+/// Wraps pieces of text to the `width` and writes every line.
+///
+/// # Arguments
+/// * `pieces` - Pieces of text of a paragraph.
+/// * `width` - The number of columns available to the paragraph.
+/// * `options` - Options of the rendering.
+///
+/// # Returns
+/// Lines of the paragraph.
+fn render_paragraph(pieces: &[Piece], width: usize, options: &TerminalOptions) -> Vec<String> {
+    wrap(pieces, width)
+        .iter()
+        .map(|line| write_line(line, options))
+        .collect()
+}
+
+/// Renders a code block, indented by two spaces. Its lines are padded to the same width,
+/// so the background forms a rectangle. Lines of code are never wrapped.
+///
+/// # Arguments
+/// * `content` - The literal content of the code block.
+/// * `options` - Options of the rendering.
+///
+/// # Returns
+/// Lines of the code block.
+fn render_code_block(content: &str, options: &TerminalOptions) -> Vec<String> {
+    let lines: Vec<String> = content.lines().map(sanitize).collect();
+    let width = lines
+        .iter()
+        .map(|line| line.chars().count())
+        .max()
+        .unwrap_or_default();
+    let style = Style {
+        code: true,
+        ..Style::default()
+    };
+
+    lines
+        .iter()
+        .map(|line| match options.color {
+            true => {
+                let padding = " ".repeat(width - line.chars().count());
+                let piece = Piece::new(format!(" {}{} ", line, padding), style);
+                format!("  {}", write_piece(&piece, options))
+            }
+            false => format!("    {}", line).trim_end().to_string(),
+        })
+        .collect()
+}
+
+/// Renders a list: items of an unordered list start with `•`, items of an ordered list with their number.
+/// The text of an item is wrapped with a hanging indent, and nested lists are indented under the text.
+/// Blocks of an item are indented under the text too, separated from it by an empty line.
+///
+/// # Arguments
+/// * `list` - The list.
+/// * `width` - The number of columns available to the list.
+/// * `options` - Options of the rendering.
+///
+/// # Returns
+/// Lines of the list.
+fn render_list(list: &List, width: usize, options: &TerminalOptions) -> Vec<String> {
+    let mut lines = Vec::new();
+
+    for (i, item) in list.items.iter().enumerate() {
+        let marker = match list.ordered {
+            true => format!("{}. ", list.start + i as u64),
+            false => String::from("• "),
+        };
+        let indent = " ".repeat(marker.chars().count());
+
+        let mut pieces = Vec::new();
+        if let Some(checked) = item.checked {
+            let checkbox = if checked { "[x] " } else { "[ ] " };
+            pieces.push(Piece::new(checkbox, Style::default()));
+        }
+        pieces.extend(render_inlines(&item.children, Style::default(), options));
+
+        let text_width = width.saturating_sub(indent.len());
+        for (j, line) in render_paragraph(&pieces, text_width, options)
+            .into_iter()
+            .enumerate()
+        {
+            let prefix = if j == 0 { &marker } else { &indent };
+            lines.push(format!("{}{}", prefix, line));
+        }
+        if let Some(nested) = &item.nested {
+            for line in render_list(nested, text_width, options) {
+                lines.push(format!("{}{}", indent, line));
+            }
+        }
+        let blocks = render_blocks(&item.blocks, text_width, options);
+        if !blocks.is_empty() {
+            lines.push(String::new());
+            lines.extend(blocks.into_iter().map(|line| match line.is_empty() {
+                true => line,
+                false => format!("{}{}", indent, line),
+            }));
+        }
+    }
+
+    lines
+}
+
+/// Renders a table with columns separated by `│` and a line under the bold header row. Cells aren't wrapped.
+///
+/// # Arguments
+/// * `alignments` - Alignments of the columns.
+/// * `header` - Cells of the header row.
+/// * `rows` - Cells of the body rows.
+/// * `options` - Options of the rendering.
+///
+/// # Returns
+/// Lines of the table.
+fn render_table(
+    alignments: &[Alignment],
+    header: &[TableCell],
+    rows: &[Vec<TableCell>],
+    options: &TerminalOptions,
+) -> Vec<String> {
+    let header_style = Style {
+        bold: true,
+        ..Style::default()
+    };
+    let cells: Vec<Vec<Vec<Piece>>> = std::iter::once((header, header_style))
+        .chain(rows.iter().map(|row| (row.as_slice(), Style::default())))
+        .map(|(row, style)| {
+            row.iter()
+                .map(|cell| {
+                    render_inlines(&cell.children, style, options)
+                        .into_iter()
+                        .map(|piece| Piece {
+                            text: piece.text.replace('\n', " "),
+                            ..piece
+                        })
+                        .collect()
+                })
+                .collect()
+        })
+        .collect();
+
+    let cell_width = |cell: &Vec<Piece>| cell.iter().map(Piece::width).sum::<usize>();
+    let columns = cells.iter().map(Vec::len).max().unwrap_or_default();
+    let widths: Vec<usize> = (0..columns)
+        .map(|column| {
+            cells
+                .iter()
+                .filter_map(|row| row.get(column))
+                .map(cell_width)
+                .max()
+                .unwrap_or_default()
+        })
+        .collect();
+
+    let separator = write_piece(
+        &Piece::new(
+            " │ ",
+            Style {
+                dim: true,
+                ..Style::default()
+            },
+        ),
+        options,
+    );
+    let mut lines: Vec<String> = cells
+        .iter()
+        .map(|row| {
+            widths
+                .iter()
+                .enumerate()
+                .map(|(column, width)| {
+                    let cell = row.get(column).cloned().unwrap_or_default();
+                    let padding = width - cell_width(&cell);
+                    let (left, right) = match alignments.get(column) {
+                        Some(Alignment::Right) => (padding, 0),
+                        Some(Alignment::Center) => (padding / 2, padding - padding / 2),
+                        _ => (0, padding),
+                    };
+                    format!(
+                        "{}{}{}",
+                        " ".repeat(left),
+                        write_line(&cell, options),
+                        " ".repeat(right)
+                    )
+                })
+                .collect::<Vec<_>>()
+                .join(&separator)
+                .trim_end()
+                .to_string()
+        })
+        .collect();
+
+    let rule = widths
+        .iter()
+        .map(|width| "─".repeat(*width))
+        .collect::<Vec<_>>()
+        .join("─┼─");
+    let rule = write_piece(
+        &Piece::new(
+            rule,
+            Style {
+                dim: true,
+                ..Style::default()
+            },
+        ),
+        options,
+    );
+    lines.insert(1, rule);
+
+    lines
+}
+
+/// Renders inline elements into pieces of styled text.
+///
+/// # Arguments
+/// * `inlines` - The inline elements.
+/// * `style` - The style of the element which contains the inline elements.
+/// * `options` - Options of the rendering.
+///
+/// # Returns
+/// Pieces of text of the elements.
+fn render_inlines(inlines: &[Inline], style: Style, options: &TerminalOptions) -> Vec<Piece> {
+    let mut pieces = Vec::new();
+
+    for inline in inlines {
+        match &inline.kind {
+            InlineKind::Text(text) => pieces.push(Piece::new(sanitize(text), style)),
+            InlineKind::Code(code) => pieces.push(Piece::new(
+                sanitize(code),
+                Style {
+                    code: true,
+                    ..style
+                },
+            )),
+            InlineKind::Strong(children) => pieces.extend(render_inlines(
+                children,
+                Style {
+                    bold: true,
+                    ..style
+                },
+                options,
+            )),
+            InlineKind::Emphasis(children) => pieces.extend(render_inlines(
+                children,
+                Style {
+                    italic: true,
+                    ..style
+                },
+                options,
+            )),
+            InlineKind::Strikethrough(children) => pieces.extend(render_inlines(
+                children,
+                Style {
+                    strikethrough: true,
+                    ..style
+                },
+                options,
+            )),
+            InlineKind::Underline(children) => pieces.extend(render_inlines(
+                children,
+                Style {
+                    underline: true,
+                    ..style
+                },
+                options,
+            )),
+            InlineKind::Link { url, children, .. } => {
+                let link_style = Style {
+                    underline: true,
+                    color: Some(LINK_COLOR),
+                    ..style
+                };
+                let text = render_inlines(children, link_style, options);
+                let same_text = text
+                    .iter()
+                    .map(|piece| piece.text.as_str())
+                    .eq([url.as_str()]);
+                pieces.extend(link_pieces(text, url, same_text, options));
+            }
+            InlineKind::Image { url, alt, .. } => {
+                let text = vec![Piece::new(
+                    format!("[image: {}]", sanitize(alt)),
+                    Style {
+                        color: Some(LINK_COLOR),
+                        ..style
+                    },
+                )];
+                pieces.extend(link_pieces(text, url, false, options));
+            }
+            InlineKind::FootnoteReference { label } => pieces.push(Piece::new(
+                format!("[{}]", sanitize(label)),
+                Style { dim: true, ..style },
+            )),
+            InlineKind::LineBreak => pieces.push(Piece::new(" ", style)),
+        }
+    }
+
+    pieces
+}
+
+/// Turns the pieces of the text of a link into a hyperlink to the `url`. Without hyperlinks,
+/// the URL is written after the text, unless the text is the URL itself.
+///
+/// # Arguments
+/// * `text` - Pieces of the text of the link.
+/// * `url` - The destination of the link.
+/// * `same_text` - Whether the text of the link is its URL.
+/// * `options` - Options of the rendering.
+///
+/// # Returns
+/// Pieces of the link.
+fn link_pieces(
+    text: Vec<Piece>,
+    url: &str,
+    same_text: bool,
+    options: &TerminalOptions,
+) -> Vec<Piece> {
+    let url = sanitize(url);
+
+    if options.hyperlinks {
+        return text
+            .into_iter()
+            .map(|piece| Piece {
+                link: Some(url.clone()),
+                ..piece
+            })
+            .collect();
+    }
+
+    let mut pieces = text;
+    if !same_text {
+        pieces.push(Piece::new(
+            format!(" ({})", url),
+            Style {
+                dim: true,
+                ..Style::default()
+            },
+        ));
+    }
+    pieces
+}
+
+/// Replaces control characters, so text of the markdown input can't contain escape sequences. Tabs become spaces.
+///
+/// # Arguments
+/// * `text` - Text of the markdown input.
+///
+/// # Returns
+/// The text without control characters, except line breaks.
+fn sanitize(text: &str) -> String {
+    text.chars()
+        .map(|c| match c {
+            '\t' => ' ',
+            c if c.is_control() && c != '\n' => '\u{FFFD}',
+            c => c,
+        })
+        .collect()
+}
+
+/// Wraps pieces of text into lines of at most `width` columns, breaking lines at whitespace.
+/// Words longer than the width are broken at the width. Consecutive whitespace is collapsed into a single space.
+///
+/// # Arguments
+/// * `pieces` - Pieces of text.
+/// * `width` - The maximum number of columns of a line.
+///
+/// # Returns
+/// Pieces of text of every line.
+fn wrap(pieces: &[Piece], width: usize) -> Vec<Vec<Piece>> {
+    let mut wrapper = Wrapper {
+        width: width.max(1),
+        ..Wrapper::default()
+    };
+
+    for piece in pieces {
+        let mut text = String::new();
+        for c in piece.text.chars() {
+            if !c.is_whitespace() {
+                text.push(c);
+                continue;
+            }
+
+            if !text.is_empty() {
+                wrapper.word.push(Piece {
+                    text: std::mem::take(&mut text),
+                    ..piece.clone()
+                });
+            }
+            wrapper.place_word();
+            wrapper.space = Some(Piece {
+                text: String::from(" "),
+                ..piece.clone()
+            });
+        }
+        if !text.is_empty() {
+            wrapper.word.push(Piece {
+                text,
+                ..piece.clone()
+            });
+        }
+    }
+    wrapper.place_word();
+    if !wrapper.line.is_empty() {
+        wrapper.lines.push(wrapper.line);
+    }
+
+    wrapper.lines
+}
+
+/// State of wrapping text into lines.
+#[derive(Debug, Default)]
+struct Wrapper {
+    /// The maximum number of columns of a line.
+    width: usize,
+    /// Finished lines.
+    lines: Vec<Vec<Piece>>,
+    /// Pieces of the current line.
+    line: Vec<Piece>,
+    /// The number of columns of the current line.
+    line_width: usize,
+    /// The whitespace before the current word, written only if the word isn't at the start of a line.
+    space: Option<Piece>,
+    /// Pieces of the current word, which may have different styles.
+    word: Vec<Piece>,
+}
+
+impl Wrapper {
+    /// Places the current word on the current line, or starts a new line if the word doesn't fit.
+    fn place_word(&mut self) {
+        let word_width: usize = self.word.iter().map(Piece::width).sum();
+        if word_width == 0 {
+            return;
+        }
+
+        if self.line_width > 0 && self.line_width + 1 + word_width > self.width {
+            self.break_line();
+        }
+        if let Some(space) = self.space.take() {
+            if self.line_width > 0 {
+                self.line.push(space);
+                self.line_width += 1;
+            }
+        }
+
+        for piece in std::mem::take(&mut self.word) {
+            let mut chars: Vec<char> = piece.text.chars().collect();
+            while self.line_width + chars.len() > self.width {
+                let rest = chars.split_off(self.width - self.line_width);
+                if !chars.is_empty() {
+                    self.line.push(Piece {
+                        text: chars.into_iter().collect(),
+                        ..piece.clone()
+                    });
+                }
+                self.break_line();
+                chars = rest;
+            }
+            self.line_width += chars.len();
+            self.line.push(Piece {
+                text: chars.into_iter().collect(),
+                ..piece
+            });
+        }
+    }
+
+    /// Finishes the current line.
+    fn break_line(&mut self) {
+        self.lines.push(std::mem::take(&mut self.line));
+        self.line_width = 0;
+    }
+}
+
+/// Writes pieces of a line, merging adjacent pieces with the same style and link.
+///
+/// # Arguments
+/// * `pieces` - Pieces of text of the line.
+/// * `options` - Options of the rendering.
+///
+/// # Returns
+/// The line with its escape sequences.
+fn write_line(pieces: &[Piece], options: &TerminalOptions) -> String {
+    let mut merged: Vec<Piece> = Vec::new();
+    for piece in pieces {
+        match merged.last_mut() {
+            Some(last) if last.style == piece.style && last.link == piece.link => {
+                last.text.push_str(&piece.text)
+            }
+            _ => merged.push(piece.clone()),
+        }
+    }
+
+    merged
+        .iter()
+        .map(|piece| write_piece(piece, options))
+        .collect()
+}
+
+/// Writes a piece of text with the SGR escape sequence of its style and the OSC 8 sequence of its link.
+///
+/// # Arguments
+/// * `piece` - The piece of text.
+/// * `options` - Options of the rendering, escape sequences are written only if they are enabled.
+///
+/// # Returns
+/// The text with its escape sequences.
+fn write_piece(piece: &Piece, options: &TerminalOptions) -> String {
+    let sgr = piece.style.sgr();
+    let text = match options.color && !sgr.is_empty() {
+        true => format!("\x1b[{}m{}\x1b[0m", sgr, piece.text),
+        false => piece.text.clone(),
+    };
+
+    match (&piece.link, options.hyperlinks) {
+        (Some(url), true) => format!("\x1b]8;;{}\x1b\\{}\x1b]8;;\x1b\\", url, text),
+        _ => text,
+    }
+}
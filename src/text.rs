@@ -0,0 +1,170 @@
+use crate::ast::{Block, BlockKind, Document, Inline, InlineKind, List};
+
+/// Options of plain text rendering.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TextOptions {
+    link_urls: bool,
+}
+
+impl TextOptions {
+    /// Creates the default options.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets whether the URL of a link is appended to its text in parentheses, e.g. `docs (https://example.com)`.
+    /// By default only the text of a link is kept.
+    pub fn link_urls(mut self, enabled: bool) -> Self {
+        self.link_urls = enabled;
+        self
+    }
+}
+
+/// Renders the `document` tree to plain text without any markup, e.g. for search indexing or previews.
+/// Blocks are separated by an empty line, the text of links and the alternative text of images are kept,
+/// code blocks are kept verbatim.
+///
+/// # Arguments
+/// * `document` - The document tree to render.
+///
+/// # Returns
+/// The plain text of the document.
+pub fn to_text(document: &Document) -> String {
+    to_text_with_options(document, &TextOptions::default())
+}
+
+/// Renders the `document` tree to plain text with the given `options`.
+///
+/// Every item of a list is written on its own line, nested items are indented by two spaces.
+/// Cells of a table row are separated by tabs. Footnote definitions are written where they are defined,
+/// while footnote references, horizontal rules and link reference definitions are dropped.
+///
+/// # Arguments
+/// * `document` - The document tree to render.
+/// * `options` - Options of the rendering.
+///
+/// # Returns
+/// The plain text of the document.
+pub fn to_text_with_options(document: &Document, options: &TextOptions) -> String {
+    let text = render_blocks(&document.blocks, options);
+    match text.is_empty() {
+        true => text,
+        false => text + "\n",
+    }
+}
+
+/// Renders blocks to plain text, separated by an empty line. Blocks without text are skipped.
+///
+/// # Arguments
+/// * `blocks` - The blocks.
+/// * `options` - Options of the rendering.
+///
+/// # Returns
+/// The plain text of the blocks without a trailing line break.
+fn render_blocks(blocks: &[Block], options: &TextOptions) -> String {
+    blocks
+        .iter()
+        .filter_map(|block| render_block(block, options))
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+/// Renders a single block to plain text.
+///
+/// # Arguments
+/// * `block` - The block.
+/// * `options` - Options of the rendering.
+///
+/// # Returns
+/// The plain text of the block, or `None` if the block has no text.
+fn render_block(block: &Block, options: &TextOptions) -> Option<String> {
+    let text = match &block.kind {
+        BlockKind::Heading { children, .. }
+        | BlockKind::Paragraph { children }
+        | BlockKind::FootnoteDefinition { children, .. } => render_inlines(children, options),
+        BlockKind::CodeBlock { content, .. } => content.clone(),
+        BlockKind::Quote { children } => render_blocks(children, options),
+        BlockKind::List(list) => render_list(list, 0, options),
+        BlockKind::Table { header, rows, .. } => std::iter::once(header)
+            .chain(rows)
+            .map(|row| {
+                row.iter()
+                    .map(|cell| render_inlines(&cell.children, options))
+                    .collect::<Vec<_>>()
+                    .join("\t")
+            })
+            .collect::<Vec<_>>()
+            .join("\n"),
+        BlockKind::HorizontalRule | BlockKind::EmptyLine | BlockKind::LinkDefinition { .. } => {
+            return None
+        }
+    };
+
+    (!text.is_empty()).then_some(text)
+}
+
+/// Renders the items of a list to plain text, one item per line.
+/// Continuation lines of an item are indented like the item, and its blocks like its nested list.
+///
+/// # Arguments
+/// * `list` - The list.
+/// * `depth` - The nesting depth of the list, `0` for a list which isn't nested.
+/// * `options` - Options of the rendering.
+///
+/// # Returns
+/// The plain text of the list without a trailing line break.
+fn render_list(list: &List, depth: usize, options: &TextOptions) -> String {
+    let indent = "  ".repeat(depth);
+    let mut lines = Vec::new();
+
+    for item in &list.items {
+        lines.push(format!(
+            "{}{}",
+            indent,
+            render_inlines(&item.children, options).replace('\n', &format!("\n{}", indent))
+        ));
+        if let Some(nested) = &item.nested {
+            lines.push(render_list(nested, depth + 1, options));
+        }
+        let blocks = render_blocks(&item.blocks, options);
+        lines.extend(blocks.lines().map(|line| match line.is_empty() {
+            true => String::new(),
+            false => format!("{}  {}", indent, line),
+        }));
+    }
+
+    lines.join("\n")
+}
+
+/// Renders inline elements to plain text. Line breaks between lines of a paragraph are kept.
+///
+/// # Arguments
+/// * `inlines` - The inline elements.
+/// * `options` - Options of the rendering.
+///
+/// # Returns
+/// The plain text of the elements.
+fn render_inlines(inlines: &[Inline], options: &TextOptions) -> String {
+    let mut text = String::new();
+
+    for inline in inlines {
+        match &inline.kind {
+            InlineKind::Text(value) | InlineKind::Code(value) => text.push_str(value),
+            InlineKind::Strong(children)
+            | InlineKind::Emphasis(children)
+            | InlineKind::Strikethrough(children)
+            | InlineKind::Underline(children) => text.push_str(&render_inlines(children, options)),
+            InlineKind::Link { url, children, .. } => {
+                text.push_str(&render_inlines(children, options));
+                if options.link_urls {
+                    text.push_str(&format!(" ({})", url));
+                }
+            }
+            InlineKind::Image { alt, .. } => text.push_str(alt),
+            InlineKind::FootnoteReference { .. } => {}
+            InlineKind::LineBreak => text.push('\n'),
+        }
+    }
+
+    text
+}
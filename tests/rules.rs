@@ -33,6 +33,26 @@ fn check_header(rule: Rule, header_string: &str) {
     }
 }
 
+/// Generates `count` inputs of up to 23 random pieces of the `alphabet`, with a linear congruential generator.
+/// The same `seed` always generates the same inputs.
+fn random_inputs(alphabet: &[&str], mut seed: u64, count: usize) -> Vec<String> {
+    let mut next = || {
+        seed = seed
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        (seed >> 33) as usize
+    };
+
+    (0..count)
+        .map(|_| {
+            let length = next() % 24;
+            (0..length)
+                .map(|_| alphabet[next() % alphabet.len()])
+                .collect()
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -57,6 +77,54 @@ mod tests {
         check_header(Rule::heading3, "### Simple header 3");
     }
 
+    #[test]
+    fn check_styled_headers() -> Result<()> {
+        let input = "## **Important** [link](x) ##\n#### Four\n##### Five *\n###### Six ######\n####### Seven\n### ###";
+        let html = str_to_html(input)?;
+
+        assert_eq!(
+            html,
+            vec![
+                "<h2><strong>Important</strong> <a href=\"x\">link</a></h2>",
+                "<h4>Four</h4>",
+                "<h5>Five *</h5>",
+                "<h6>Six</h6>",
+                "<p>####### Seven</p>",
+                "<h3></h3>",
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn check_setext_headers() -> Result<()> {
+        let input = "Main *title*\n===\nSub\ntitle\n  ---\n\n---";
+        let mut pairs = parse_by_rule(Rule::markdown, input)?;
+        let markdown_inner: Vec<Rule> = pairs
+            .next()
+            .ok_or_else(|| anyhow!("Expected a markdown root, but found none"))?
+            .into_inner()
+            .map(|pair| pair.as_rule())
+            .collect();
+        assert_eq!(
+            markdown_inner,
+            vec![
+                Rule::setext_heading1,
+                Rule::setext_heading2,
+                Rule::empty_line,
+                Rule::horizontal_rule,
+                Rule::EOI,
+            ]
+        );
+
+        let html = str_to_html(input)?;
+        assert_eq!(html[0], "<h1>Main <em>title</em></h1>");
+        assert_eq!(html[1], "<h2>Sub title</h2>");
+
+        Ok(())
+    }
+
     #[test]
     #[should_panic]
     fn check_wrong_headers() {
@@ -232,6 +300,806 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn check_reference_links() -> Result<()> {
+        let input = "[Docs][Main  Page], [home][], [Home] and [nothing][here].\n![Logo]\n\n[main page]: https://example.com \"Main\"\n[HOME]: /\n[logo]: /logo.png";
+
+        let mut pairs = parse_by_rule(
+            Rule::link_definition,
+            "[main page]: https://example.com \"Main\"",
+        )?;
+        let mut definition_inner = pairs
+            .next()
+            .ok_or_else(|| anyhow!("Expected a link_definition, but found none"))?
+            .into_inner();
+        let inner_rules: Vec<(Rule, &str)> = definition_inner
+            .by_ref()
+            .map(|pair| (pair.as_rule(), pair.as_str()))
+            .collect();
+        assert_eq!(
+            inner_rules,
+            vec![
+                (Rule::link_label, "main page"),
+                (Rule::link_destination, "https://example.com"),
+                (Rule::link_title, "\"Main\""),
+            ]
+        );
+
+        let html = str_to_html(input)?;
+        assert_eq!(
+            html,
+            vec![
+                "<p><a href=\"https://example.com\" title=\"Main\">Docs</a>, <a href=\"/\">home</a>, \
+                 <a href=\"/\">Home</a> and [nothing][here].<br><img src=\"/logo.png\" alt=\"Logo\"></p>",
+                "<br/>",
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn check_link_titles() -> Result<()> {
+        let mut pairs = parse_by_rule(Rule::inline_link, "[a](<my file.md> 'Title')")?;
+        let inner_rules: Vec<(Rule, &str)> = pairs
+            .next()
+            .ok_or_else(|| anyhow!("Expected an inline_link, but found none"))?
+            .into_inner()
+            .map(|pair| (pair.as_rule(), pair.as_str()))
+            .collect();
+        assert_eq!(
+            inner_rules,
+            vec![
+                (Rule::link_text, "a"),
+                (Rule::url, "<my file.md>"),
+                (Rule::link_title, "'Title'"),
+            ]
+        );
+
+        let html =
+            str_to_html("[a](<my file.md> \"A <b>\") ![i](/p(1).png (Photo)) [b](/ä?q=%20&x)")?;
+        assert_eq!(
+            html,
+            vec![
+                "<p><a href=\"my%20file.md\" title=\"A &lt;b&gt;\">a</a> \
+                 <img src=\"/p(1).png\" alt=\"i\" title=\"Photo\"> \
+                 <a href=\"/%C3%A4?q=%20&amp;x\">b</a></p>",
+            ]
+        );
+
+        let html = str_to_html("[a](x\" onmouseover=\"alert(1)) ![x\"y](<a\"b>)")?;
+        assert_eq!(
+            html,
+            vec![
+                "<p>[a](x\" onmouseover=\"alert(1)) \
+                 <img src=\"a%22b\" alt=\"x&quot;y\"></p>",
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn check_styled_link_text() -> Result<()> {
+        let input = "[**b** and *i*](http://x) [**r**][a] [\\]`c`](y)\n\n[a]: /r";
+
+        let html = str_to_html(input)?;
+        assert_eq!(
+            html,
+            vec![
+                "<p><a href=\"http://x\"><strong>b</strong> and <em>i</em></a> \
+                 <a href=\"/r\"><strong>r</strong></a> \
+                 <a href=\"y\">]<code>c</code></a></p>",
+                "<br/>",
+            ]
+        );
+
+        let document = parse_document(input)?;
+        let [paragraph, ..] = document.blocks.as_slice() else {
+            bail!("Expected a paragraph, but found {:#?}", document.blocks);
+        };
+        let BlockKind::Paragraph { children } = &paragraph.kind else {
+            bail!("Expected a paragraph, but found {:#?}", paragraph.kind);
+        };
+        let InlineKind::Link {
+            children: link_children,
+            ..
+        } = &children[0].kind
+        else {
+            bail!("Expected a link, but found {:#?}", children[0].kind);
+        };
+        assert!(
+            matches!(&link_children[0].kind, InlineKind::Strong(inner) if inner[0].kind == InlineKind::Text(String::from("b")))
+        );
+        assert_eq!(
+            link_children[1].kind,
+            InlineKind::Text(String::from(" and "))
+        );
+        assert!(
+            matches!(&link_children[2].kind, InlineKind::Emphasis(inner) if inner[0].kind == InlineKind::Text(String::from("i")))
+        );
+        assert_eq!(
+            &input[link_children[2].span.start.offset..link_children[2].span.end.offset],
+            "*i*"
+        );
+
+        let InlineKind::Link {
+            url,
+            children: reference_children,
+            ..
+        } = &children[2].kind
+        else {
+            bail!(
+                "Expected a reference link, but found {:#?}",
+                children[2].kind
+            );
+        };
+        assert_eq!(url, "/r");
+        assert!(
+            matches!(&reference_children[0].kind, InlineKind::Strong(inner) if inner[0].kind == InlineKind::Text(String::from("r")))
+        );
+
+        let formatted = format_markdown(input)?;
+        assert_eq!(
+            formatted,
+            "[**b** and *i*](http://x) [**r**](/r) [\\]`c`](y)\n\n[a]: /r\n"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn check_document_tree() -> Result<()> {
+        let input = "## **Bold** [link](/url \"Title\")\n```rust\nfn main() {}\n```\n- [x] _done_\n  - nested\n> quote";
+        let document = parse_document(input)?;
+        let text = |text: &str| InlineKind::Text(String::from(text));
+
+        let [heading, code_block, list, quote] = document.blocks.as_slice() else {
+            bail!("Expected four blocks, but found {:#?}", document.blocks);
+        };
+
+        let BlockKind::Heading { level: 2, children } = &heading.kind else {
+            bail!("Expected a level-2 heading, but found {:#?}", heading.kind);
+        };
+        assert!(
+            matches!(&children[0].kind, InlineKind::Strong(inner) if inner[0].kind == text("Bold"))
+        );
+        assert_eq!(children[1].kind, text(" "));
+        let InlineKind::Link {
+            url,
+            title,
+            children: link_children,
+        } = &children[2].kind
+        else {
+            bail!("Expected a link, but found {:#?}", children[2].kind);
+        };
+        assert_eq!(url, "/url");
+        assert_eq!(title.as_deref(), Some("Title"));
+        assert_eq!(link_children[0].kind, text("link"));
+
+        assert_eq!(
+            code_block.kind,
+            BlockKind::CodeBlock {
+                lang: Some(String::from("rust")),
+                content: String::from("fn main() {}"),
+            }
+        );
+
+        let BlockKind::List(list) = &list.kind else {
+            bail!("Expected a list, but found {:#?}", list.kind);
+        };
+        assert!(!list.ordered);
+        assert_eq!(list.items[0].checked, Some(true));
+        assert!(
+            matches!(&list.items[0].children[0].kind, InlineKind::Emphasis(inner) if inner[0].kind == text("done"))
+        );
+        let nested = list.items[0]
+            .nested
+            .as_ref()
+            .ok_or_else(|| anyhow!("Expected a nested list, but found none"))?;
+        assert_eq!(nested.items[0].checked, None);
+        assert_eq!(nested.items[0].children[0].kind, text("nested"));
+
+        let BlockKind::Quote { children } = &quote.kind else {
+            bail!("Expected a quote, but found {:#?}", quote.kind);
+        };
+        assert!(
+            matches!(&children[0].kind, BlockKind::Paragraph { children } if children[0].kind == text("quote"))
+        );
+
+        assert_eq!(to_html(&document), str_to_html(input)?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn check_source_spans() -> Result<()> {
+        let input = "# Title\n\n> quoted **bold**\n> - item\n\n| a | b |\n|---|---|\n| 1 |";
+        let document = parse_document(input)?;
+
+        let heading = &document.blocks[0];
+        assert_eq!(
+            heading.span.start,
+            Position {
+                line: 1,
+                column: 1,
+                offset: 0
+            }
+        );
+        assert_eq!(
+            heading.span.end,
+            Position {
+                line: 1,
+                column: 8,
+                offset: 7
+            }
+        );
+
+        let quote = &document.blocks[2];
+        assert_eq!((quote.span.start.line, quote.span.end.line), (3, 4));
+        let BlockKind::Quote { children } = &quote.kind else {
+            bail!("Expected a quote, but found {:#?}", quote.kind);
+        };
+        let BlockKind::Paragraph { children: inlines } = &children[0].kind else {
+            bail!("Expected a paragraph, but found {:#?}", children[0].kind);
+        };
+        let bold = &inlines[1];
+        assert_eq!(
+            &input[bold.span.start.offset..bold.span.end.offset],
+            "**bold**"
+        );
+        assert_eq!(
+            bold.span.start,
+            Position {
+                line: 3,
+                column: 10,
+                offset: 18
+            }
+        );
+        assert_eq!(
+            (children[1].span.start.line, children[1].span.start.column),
+            (4, 3)
+        );
+
+        let html = to_html_with_options(&document, &HtmlOptions::new().sourcepos(true));
+        assert_eq!(
+            html,
+            vec![
+                "<h1 data-sourcepos=\"1:1-1:7\">Title</h1>",
+                "<br/>",
+                "<blockquote data-sourcepos=\"3:1-4:8\"><p data-sourcepos=\"3:3-3:17\">quoted <strong>bold</strong></p>\
+                 <ul data-sourcepos=\"4:3-4:8\"><li data-sourcepos=\"4:3-4:8\">item</li></ul></blockquote>",
+                "<br/>",
+                "<table data-sourcepos=\"6:1-8:5\"><thead><tr><th data-sourcepos=\"6:2-6:4\">a</th>\
+                 <th data-sourcepos=\"6:6-6:8\">b</th></tr></thead><tbody><tr><td data-sourcepos=\"8:2-8:4\">1</td>\
+                 <td data-sourcepos=\"8:6-8:6\"></td></tr></tbody></table>",
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn check_syntax_errors() -> Result<()> {
+        let Err(ErrorParse::SyntaxError(error)) =
+            str_to_html_strict("# Title\n> quote\n> **unclosed")
+        else {
+            bail!("Expected a syntax error");
+        };
+
+        assert_eq!((error.line, error.column, error.offset), (3, 3, 18));
+        assert_eq!(error.snippet, "> **unclosed");
+        assert_eq!(error.expected, ["closing `**`"]);
+        assert_eq!(
+            error.to_string(),
+            "expected closing `**` at line 3, column 3\n  |\n3 | > **unclosed\n  |   ^"
+        );
+
+        let Err(ErrorParse::SyntaxError(error)) = parse_by_rule(Rule::bold, "**unclosed") else {
+            bail!("Expected a syntax error");
+        };
+
+        assert_eq!((error.line, error.column, error.offset), (1, 11, 10));
+        assert!(error.expected.contains(&String::from("bold text")));
+        assert!(error
+            .expected
+            .iter()
+            .all(|expected| !expected.contains('_')));
+
+        Ok(())
+    }
+
+    #[test]
+    fn check_lenient_parsing() -> Result<()> {
+        assert_eq!(
+            str_to_html("2 * 3 = 6 and **unclosed *with* ~~ my_var")?,
+            ["<p>2 * 3 = 6 and **unclosed <em>with</em> ~~ my_var</p>"]
+        );
+        assert_eq!(
+            str_to_html("# A *title\n| a | b_ |\n|---|---|\n| **c | d |")?,
+            [
+                "<h1>A *title</h1>",
+                "<table><thead><tr><th>a</th><th>b_</th></tr></thead><tbody><tr><td>**c</td><td>d</td></tr></tbody></table>"
+            ]
+        );
+        assert!(str_to_html_strict("2 * 3").is_err());
+
+        // Every input built from markdown punctuation is converted without errors.
+        let alphabet = [
+            "*", "**", "_", "__", "~~", "`", "[", "]", "(", ")", "!", "^", ":", "#", ">", "-", "|",
+            "\\", "<", "\"", " ", "\t", "\n", "\r\n", "1.", "a", "ü", "```",
+        ];
+        for input in random_inputs(&alphabet, 42, 2000) {
+            if let Err(err) = str_to_html(&input) {
+                bail!("Failed to convert {:?}: {}", input, err);
+            }
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn check_parse_options() -> Result<()> {
+        let comments = ParseOptions::new().headings(false).images(false);
+        assert_eq!(
+            str_to_html_with_options("# **Hi**\n\n![cat](cat.png) and [link](/a)", &comments)?,
+            [
+                "<p># <strong>Hi</strong></p>",
+                "<br/>",
+                "<p>![cat](cat.png) and <a href=\"/a\">link</a></p>"
+            ]
+        );
+
+        let options = ParseOptions::new()
+            .underline(false)
+            .task_lists(false)
+            .footnotes(false);
+        let (document, warnings) =
+            parse_document_with_options("- [x] __a *b*__ [^x]\n\n[^x]: Note", &options)?;
+        assert!(warnings.is_empty());
+        assert_eq!(
+            to_html(&document),
+            [
+                "<ul><li>[x] __a <em>b</em>__ [^x]</li></ul>",
+                "<br/>",
+                "<p>[^x]: Note</p>"
+            ]
+        );
+        assert!(parse_tasks("- [x] Done")?.len() == 1);
+
+        let plain = ParseOptions::new()
+            .lists(false)
+            .headings(false)
+            .blockquotes(false)
+            .horizontal_rules(false);
+        assert_eq!(
+            str_to_html_with_options("text\n- item\n# title\n> quote\n***\nend", &plain)?,
+            ["<p>text<br>- item<br># title<br>&gt; quote<br>***<br>end</p>"]
+        );
+        assert_eq!(
+            str_to_html_with_options("text\n- item", &ParseOptions::new().lists(false))?,
+            ["<p>text<br>- item</p>"]
+        );
+
+        let Err(ErrorParse::SyntaxError(error)) =
+            str_to_html_with_options("a **b", &ParseOptions::new().strict(true))
+        else {
+            bail!("Expected a syntax error");
+        };
+        assert_eq!(error.column, 3);
+
+        Ok(())
+    }
+
+    #[test]
+    fn check_custom_renderer() -> Result<()> {
+        struct SiteRenderer {
+            code_blocks: usize,
+        }
+
+        impl Renderer for SiteRenderer {
+            fn link(&mut self, url: &str, _title: Option<&str>, content: &str) -> String {
+                format!(
+                    "<a href=\"{}\" rel=\"noopener\">{}</a>",
+                    encode_url(url),
+                    content
+                )
+            }
+
+            fn code_block(&mut self, lang: Option<&str>, code: &str, _attributes: &str) -> String {
+                self.code_blocks += 1;
+                format!(
+                    "<x-code id=\"{}\" lang=\"{}\">{}</x-code>",
+                    self.code_blocks,
+                    escape_html(lang.unwrap_or("text")),
+                    escape_html(code)
+                )
+            }
+        }
+
+        let document = parse_document("A **[link](/a?b&c)**\n```rust\nlet a = 1 < 2;\n```")?;
+        let mut renderer = SiteRenderer { code_blocks: 0 };
+        assert_eq!(
+            to_html_with_renderer(&document, &HtmlOptions::new(), &mut renderer),
+            [
+                "<p>A <strong><a href=\"/a?b&amp;c\" rel=\"noopener\">link</a></strong></p>",
+                "<x-code id=\"1\" lang=\"rust\">let a = 1 &lt; 2;</x-code>"
+            ]
+        );
+        assert_eq!(
+            to_html_with_renderer(&document, &HtmlOptions::new(), &mut HtmlRenderer),
+            to_html(&document)
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn check_streaming_output() -> Result<()> {
+        let document = parse_document("# Title\n\nText[^1]\n\n[^1]: Note")?;
+        let expected: String = to_html(&document)
+            .iter()
+            .map(|line| format!("{}\n", line))
+            .collect();
+
+        let mut bytes = Vec::new();
+        write_html(
+            &document,
+            &HtmlOptions::new(),
+            &mut HtmlRenderer,
+            &mut bytes,
+        )?;
+        assert_eq!(String::from_utf8(bytes)?, expected);
+
+        let mut text = String::new();
+        write_html_fmt(&document, &HtmlOptions::new(), &mut HtmlRenderer, &mut text)?;
+        assert_eq!(text, expected);
+
+        let directory = std::env::temp_dir();
+        let md_path = directory.join("rins_markdown_parser_streaming.md");
+        let html_path = directory.join("rins_markdown_parser_streaming.html");
+        std::fs::write(&md_path, "# Title\r\n\r\nText[^1]\n\n[^1]: Note")?;
+        md_to_html_file(&md_path, &html_path)?;
+        assert_eq!(std::fs::read_to_string(&html_path)?, expected);
+
+        Ok(())
+    }
+
+    #[test]
+    fn check_events() -> Result<()> {
+        let input = "# Title\n\nA *b* `c` ![i](i.png) [^1]\nnext\n\n> - [x] one\n>   1. two\n\n| a | b |\n|:--|--:|\n| 1 | 2 |\n\n```rust\nx < 1\n```\n---\n[^1]: Note";
+        assert_eq!(
+            events_to_html(parse_events(input)?),
+            to_html(&parse_document(input)?)
+        );
+
+        let events: Vec<Event> = parse_events("## Hi *you*")?.collect();
+        assert_eq!(
+            events,
+            [
+                Event::Start(Tag::Heading(2)),
+                Event::Text(String::from("Hi ")),
+                Event::Start(Tag::Emphasis),
+                Event::Text(String::from("you")),
+                Event::End(Tag::Emphasis),
+                Event::End(Tag::Heading(2)),
+            ]
+        );
+
+        // Line breaks of a paragraph are soft breaks, empty lines have their own event.
+        let events: Vec<Event> = parse_events("a\nb\n\nc")?
+            .filter(|event| {
+                matches!(
+                    event,
+                    Event::SoftBreak | Event::HardBreak | Event::EmptyLine
+                )
+            })
+            .collect();
+        assert_eq!(events, [Event::SoftBreak, Event::EmptyLine]);
+
+        // Soft breaks are rendered as `<br>`, the same as hard breaks inserted into the events.
+        assert_eq!(events_to_html(parse_events("a\nb")?), str_to_html("a\nb")?);
+        let events = parse_events("a\nb")?.map(|event| match event {
+            Event::SoftBreak => Event::HardBreak,
+            event => event,
+        });
+        assert_eq!(events_to_html(events), str_to_html("a\nb")?);
+
+        // Drops images and upper-cases text of headings.
+        let mut image_depth = 0;
+        let mut in_heading = false;
+        let filtered = parse_events("# Hello ![logo](l.png)\nSee ![it](i.png) *here*")?
+            .filter(|event| {
+                match event {
+                    Event::Start(Tag::Image { .. }) => image_depth += 1,
+                    Event::End(Tag::Image { .. }) => {
+                        image_depth -= 1;
+                        return false;
+                    }
+                    _ => {}
+                }
+                image_depth == 0
+            })
+            .map(|event| match event {
+                Event::Start(Tag::Heading(_)) | Event::End(Tag::Heading(_)) => {
+                    in_heading = matches!(event, Event::Start(_));
+                    event
+                }
+                Event::Text(text) if in_heading => Event::Text(text.to_uppercase()),
+                event => event,
+            });
+        assert_eq!(
+            events_to_html(filtered),
+            ["<h1>HELLO </h1>", "<p>See  <em>here</em></p>"]
+        );
+
+        Ok(())
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn check_json_schema() -> Result<()> {
+        let input = "# Title\n\n> - [x] **done** [link](/a)\n\n| a |\n|:-:|\n| 1 |\n\n---";
+        let json = str_to_json(input)?;
+        assert!(json.starts_with(&format!("{{\"version\":{},", JSON_SCHEMA_VERSION)));
+        assert!(json.contains("{\"type\":\"heading\",\"value\":{\"level\":1,"));
+        assert!(json.contains("\"alignments\":[\"center\"]"));
+        assert!(json.contains("{\"type\":\"horizontal_rule\",\"span\":"));
+        assert_eq!(document_from_json(&json)?, parse_document(input)?);
+
+        let other_version = json.replacen(
+            &format!("\"version\":{}", JSON_SCHEMA_VERSION),
+            &format!("\"version\":{}", JSON_SCHEMA_VERSION + 1),
+            1,
+        );
+        let Err(ErrorParse::JsonError(message)) = document_from_json(&other_version) else {
+            bail!("A document with another schema version should be rejected");
+        };
+        assert!(message.contains("schema version"));
+        assert!(matches!(
+            document_from_json("{\"version\":1}"),
+            Err(ErrorParse::JsonError(_))
+        ));
+
+        Ok(())
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn check_mdast_output() -> Result<()> {
+        let mdast = str_to_mdast(
+            "## *a* **b** ~~c~~\n\n> [d](/e \"f\") ![g](h.png)\n\n```rust\nx\n```\n---",
+        )?;
+        assert!(mdast.ends_with(
+            "\"position\":{\"end\":{\"column\":4,\"line\":8,\"offset\":64},\"start\":{\"column\":1,\"line\":1,\"offset\":0}},\"type\":\"root\"}"
+        ));
+        for expected in [
+            "\"depth\":2,",
+            "\"type\":\"emphasis\"",
+            "\"type\":\"strong\"",
+            "\"type\":\"delete\"",
+            "\"type\":\"blockquote\"",
+            "\"title\":\"f\",\"type\":\"link\",\"url\":\"/e\"",
+            "{\"alt\":\"g\",",
+            "\"title\":null,\"type\":\"image\",\"url\":\"h.png\"",
+            "{\"lang\":\"rust\",\"meta\":null,",
+            "\"type\":\"code\",\"value\":\"x\"",
+            "\"type\":\"thematicBreak\"",
+        ] {
+            if !mdast.contains(expected) {
+                bail!("Expected {} in {}", expected, mdast);
+            }
+        }
+        assert!(!mdast.contains("empty_line") && !mdast.contains("\"span\""));
+
+        Ok(())
+    }
+
+    #[test]
+    fn check_formatter() -> Result<()> {
+        let input = "Title\n=====\n\n\n##   Sub _x_ ##\n+ one __u__ *_a_*\n+ [x] two\n    3) a\n|a|b|\n|:-|-:|\n|cell|\\|\n***\nKept \\# and 1. and [ref]\n\n[ref]: /u 'T'";
+        let expected = "# Title\n\n## Sub *x*\n\n- one __u__ _*a*_\n- [x] two\n  3. a\n\n| a    | b   |\n| :--- | --: |\n| cell | \\|  |\n\n---\n\nKept \\# and 1. and [ref](/u \"T\")\n\n[ref]: /u \"T\"\n";
+        let formatted = format_markdown(input)?;
+        assert_eq!(formatted, expected);
+        assert_eq!(format_markdown(&formatted)?, formatted);
+        assert_eq!(format_markdown("")?, "");
+
+        // Text which would start a block or styled text is escaped.
+        let formatted = format_markdown("\\# a\n\\- b\n1\\. c\nd \\*e\\*")?;
+        assert_eq!(formatted, "\\# a\n\\- b\n1\\. c\nd \\*e\\*\n");
+
+        // A multi-line setext heading is written as an ATX heading, its lines are joined with a space.
+        let input = "Multi\nline *title*\n===\ntext";
+        let formatted = format_markdown(input)?;
+        assert_eq!(formatted, "# Multi line *title*\n\ntext\n");
+        assert_eq!(str_to_html(&formatted)?[0], str_to_html(input)?[0]);
+
+        // Blank lines after an escaped line break still separate paragraphs.
+        for input in ["a\\\n\n\nb", "===\\\n\n\nb"] {
+            let formatted = format_markdown(input)?;
+            assert_eq!(formatted, format!("{}\n", input));
+            assert_eq!(str_to_html(&formatted)?, str_to_html(input)?);
+        }
+
+        // Blocks which can't be formatted without changing their meaning are reported.
+        let (formatted, warnings) = format_markdown_with_warnings("# x\n\nc\na\\\n\n\nb")?;
+        assert_eq!(formatted, "# x\n\nc\na\\\n\n\nb\n");
+        let [ParseWarning::Unformatted { span }] = warnings.as_slice() else {
+            bail!("Expected one unformatted block, but found {:#?}", warnings);
+        };
+        assert_eq!((span.start.line, span.start.offset), (3, 5));
+        assert_eq!(
+            warnings[0].to_string(),
+            "Lines 3-4 can't be formatted without changing its meaning, it's kept unchanged"
+        );
+        assert!(format_markdown_with_warnings(expected)?.1.is_empty());
+
+        let alphabet = [
+            "*",
+            "**",
+            "_",
+            "__",
+            "~~",
+            "`",
+            "[",
+            "]",
+            "(",
+            ")",
+            "!",
+            "#",
+            ">",
+            "-",
+            "|",
+            "\\",
+            " ",
+            "\n",
+            "1.",
+            "a",
+            "*a*",
+            "**b**",
+            "__c__",
+            "[l](/u)",
+            "| a |\n|---|\n",
+        ];
+        for input in random_inputs(&alphabet, 7, 200) {
+            let formatted = format_markdown(&input)?;
+            if format_markdown(&formatted)? != formatted {
+                bail!("Formatting of {:?} is not idempotent", input);
+            }
+        }
+
+        let md_path = std::env::temp_dir().join("rins_markdown_parser_fmt.md");
+        std::fs::write(&md_path, "+ item")?;
+        assert_eq!(format_md_file(&md_path, true)?, (true, Vec::new()));
+        assert_eq!(std::fs::read_to_string(&md_path)?, "+ item");
+        assert_eq!(format_md_file(&md_path, false)?, (true, Vec::new()));
+        assert_eq!(std::fs::read_to_string(&md_path)?, "- item\n");
+        assert_eq!(format_md_file(&md_path, true)?, (false, Vec::new()));
+        std::fs::write(&md_path, "a\\\n\n\nb\n")?;
+        let (changed, warnings) = format_md_file(&md_path, true)?;
+        assert!(!changed && warnings.len() == 1);
+
+        Ok(())
+    }
+
+    #[test]
+    fn check_text_renderer() -> Result<()> {
+        let input = "# Hi *there*\nA \\*b\\* & [link](/u) ![alt](i.png) `<c>`[^1]\n\n> - one\n>   - two\n\n| a | b |\n|---|---|\n| 1 | 2 |\n\n```\nx <y>\n```\n---\n[^1]: Note";
+        let document = parse_document(input)?;
+        assert_eq!(
+            to_text(&document),
+            "Hi there\n\nA *b* & link alt <c>\n\none\n  two\n\na\tb\n1\t2\n\nx <y>\n\nNote\n"
+        );
+        assert_eq!(str_to_text(input)?, to_text(&document));
+
+        let with_urls = to_text_with_options(
+            &parse_document("See [docs](https://a.b) and ![img](i.png)")?,
+            &TextOptions::new().link_urls(true),
+        );
+        assert_eq!(with_urls, "See docs (https://a.b) and img\n");
+        assert_eq!(str_to_text("")?, "");
+
+        Ok(())
+    }
+
+    #[test]
+    fn check_terminal_renderer() -> Result<()> {
+        let input = "## Hi **b** [l](https://a.b)\n\n> q\n\n```\nab\nc\n```";
+        let colored = str_to_terminal(input, &TerminalOptions::new())?;
+        assert_eq!(
+            colored,
+            "\x1b[1;34mHi b \x1b[0m\x1b]8;;https://a.b\x1b\\\x1b[1;4;34ml\x1b[0m\x1b]8;;\x1b\\\n\n\
+             \x1b[2m│\x1b[0m q\n\n  \x1b[100m ab \x1b[0m\n  \x1b[100m c  \x1b[0m\n"
+        );
+
+        let options = TerminalOptions::new()
+            .color(false)
+            .hyperlinks(false)
+            .width(12);
+        let plain = str_to_terminal(
+            "A *long* line with [docs](/d) \x1b[31m\n\n- [x] item text",
+            &options,
+        )?;
+        assert_eq!(
+            plain,
+            "A long line\nwith docs\n(/d) �[31m\n\n• [x] item\n  text\n"
+        );
+        let Some(line) = plain.lines().map(|line| line.chars().count()).max() else {
+            bail!("Expected rendered lines, but found none");
+        };
+        assert!(line <= 12);
+
+        Ok(())
+    }
+
+    #[test]
+    fn check_latex_renderer() -> Result<()> {
+        let input = "# A & *b*\n\n50% of $1 #2_x ~^ {y} \\\\ <|>[^n] again[^n]\n\n> **s** __u__ ~~d~~ `c_d`\n\n3. [x] [l](https://e.com/a#b%20c)\n   - ![i](p.png)\n\n```rust\nlet s = \"%_\";\n```\n\n| a | b |\n|:-:|-:|\n| 1 | 2 |\n\n---\n\n[^n]: The *note*";
+        assert_eq!(
+            str_to_latex(input)?,
+            "\\section{A \\& \\emph{b}}\n\n\
+             50\\% of \\$1 \\#2\\_x \\textasciitilde{}\\textasciicircum{} \\{y\\} \\textbackslash{} \\textless{}\\textbar{}\\textgreater{}\\footnote{The \\emph{note}} again\\footnotemark[1]\n\n\
+             \\begin{quote}\n\\textbf{s} \\underline{u} \\sout{d} \\texttt{c\\_d}\n\\end{quote}\n\n\
+             \\begin{enumerate}\n  \\setcounter{enumi}{2}\n  \\item[{[x]}]{} \\href{https://e.com/a\\#b\\%20c}{l}\n  \\begin{itemize}\n    \\item{} \\includegraphics{p.png}\n  \\end{itemize}\n\\end{enumerate}\n\n\
+             \\begin{verbatim}\nlet s = \"%_\";\n\\end{verbatim}\n\n\
+             \\begin{tabular}{cr}\n  \\textbf{a} & \\textbf{b} \\\\\n  \\hline\n  1 & 2 \\\\\n\\end{tabular}\n\n\\hrule\n"
+        );
+
+        let options = LatexOptions::new().standalone(true).listings(true);
+        let latex = to_latex_with_options(&parse_document("```rust\nfn f() {}\n```")?, &options);
+        let Some(body) = latex.split("\\begin{document}\n\n").nth(1) else {
+            bail!("Expected a preamble, but found none");
+        };
+        assert!(latex.starts_with("\\documentclass{article}\n"));
+        assert!(latex.contains("\\usepackage{listings}\n"));
+        assert_eq!(
+            body,
+            "\\begin{lstlisting}[language=rust]\nfn f() {}\n\\end{lstlisting}\n\n\\end{document}\n"
+        );
+        assert_eq!(escape_latex("a_b"), "a\\_b");
+
+        // Text which starts with `[` isn't an optional argument, code can't end its environment.
+        assert_eq!(
+            str_to_latex("- [a] b\n\n| a |\n|---|\n| [c] |")?,
+            "\\begin{itemize}\n  \\item{} [a] b\n\\end{itemize}\n\n\\begin{tabular}{l}\n  \\textbf{a} \\\\\n  \\hline\n  {}[c] \\\\\n\\end{tabular}\n"
+        );
+        // A row which starts with `*` isn't read as a starred line break of the previous row.
+        assert_eq!(
+            str_to_latex("| a | b |\n|---|---|\n| 1 | 2 |\n| *x | y |")?,
+            "\\begin{tabular}{ll}\n  \\textbf{a} & \\textbf{b} \\\\\n  \\hline\n  1 & 2 \\\\\n  {}*x & y \\\\\n\\end{tabular}\n"
+        );
+        assert_eq!(
+            str_to_latex("```\n\\end{verbatim}\n```")?,
+            "\\begin{lstlisting}\n\\end{verbatim}\n\\end{lstlisting}\n"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn check_man_renderer() -> Result<()> {
+        let input = "# tool(8) - does things\n\n## OPTIONS\n\n**-v** *more* `a\\\\b`\n.dot line\n\n### Sub\n\n> quote\n\n```\n.x --y\n```";
+        assert_eq!(
+            str_to_man(input)?,
+            ".TH \"TOOL\" \"8\"\n.SH NAME\ntool \\- does things\n.SH OPTIONS\n.PP\n\\fB\\-v\\fR \\fImore\\fR \\fBa\\e\\eb\\fR\n\\&.dot line\n\
+             .SS Sub\n.RS\n.PP\nquote\n.RE\n.PP\n.RS 4\n.nf\n\\&.x \\-\\-y\n.fi\n.RE\n"
+        );
+
+        let options = ManOptions::new()
+            .section("5")
+            .manual("File Formats")
+            .date("2024-01-01");
+        let man = to_man_with_options(&parse_document("# config\n\n- one")?, &options);
+        assert_eq!(
+            man,
+            ".TH \"CONFIG\" \"5\" \"2024\\-01\\-01\" \"\" \"File Formats\"\n.IP \\(bu 2\none\n"
+        );
+        assert_eq!(escape_roff("a-b\\c"), "a\\-b\\ec");
+        assert!(str_to_man("> | a |\n> |---|\n> | 1 |")?.starts_with("'\\\" t\n.RS\n.TS\n"));
+
+        Ok(())
+    }
+
     #[test]
     fn check_inline_image() -> Result<()> {
         let input = "![alternative text](https://example.com/image.png)";
@@ -417,48 +1285,67 @@ mod tests {
         assert_eq!(pair.as_rule(), Rule::quote);
 
         let mut quote_inner = pair.into_inner();
-        let paragraph = quote_inner
-            .next()
-            .ok_or_else(|| anyhow!("Expected a paragraph within the quote, but found none"))?;
-        assert_eq!(paragraph.as_rule(), Rule::paragraph);
-
-        let mut paragraph_inner = paragraph.into_inner();
 
-        let line1 = paragraph_inner
+        let line1 = quote_inner
             .next()
-            .ok_or_else(|| anyhow!("Expected the first paragraph_line, but found none"))?;
-        assert_eq!(line1.as_rule(), Rule::paragraph_line);
+            .ok_or_else(|| anyhow!("Expected the first quote_line, but found none"))?;
+        assert_eq!(line1.as_rule(), Rule::quote_line);
 
-        let plain_text1 = line1
+        let text1 = line1
             .into_inner()
             .next()
-            .ok_or_else(|| anyhow!("Expected plain_text in the first line, but found none"))?;
-        assert_eq!(plain_text1.as_rule(), Rule::plain_text);
-        assert_eq!(plain_text1.as_str(), "This is a text in a quote");
+            .ok_or_else(|| anyhow!("Expected quote_line_text in the first line, but found none"))?;
+        assert_eq!(text1.as_rule(), Rule::quote_line_text);
+        assert_eq!(text1.as_str(), "This is a text in a quote");
 
-        let line2 = paragraph_inner
+        let line2 = quote_inner
             .next()
-            .ok_or_else(|| anyhow!("Expected the second paragraph_line, but found none"))?;
-        assert_eq!(line2.as_rule(), Rule::paragraph_line);
+            .ok_or_else(|| anyhow!("Expected the second quote line, but found none"))?;
+        assert_eq!(line2.as_rule(), Rule::quote_lazy_line);
 
-        let plain_text2 = line2
-            .into_inner()
-            .next()
-            .ok_or_else(|| anyhow!("Expected plain_text in the second line, but found none"))?;
-        assert_eq!(plain_text2.as_rule(), Rule::plain_text);
-        assert_eq!(plain_text2.as_str(), "this is also a part of a quote");
+        let text2 = line2.into_inner().next().ok_or_else(|| {
+            anyhow!("Expected quote_line_text in the second line, but found none")
+        })?;
+        assert_eq!(text2.as_str(), "this is also a part of a quote");
 
-        let line3 = paragraph_inner
+        let line3 = quote_inner
             .next()
-            .ok_or_else(|| anyhow!("Expected the third paragraph_line, but found none"))?;
-        assert_eq!(line3.as_rule(), Rule::paragraph_line);
+            .ok_or_else(|| anyhow!("Expected the third quote line, but found none"))?;
+        assert_eq!(line3.as_rule(), Rule::quote_lazy_line);
 
-        let plain_text3 = line3
+        let text3 = line3
             .into_inner()
             .next()
-            .ok_or_else(|| anyhow!("Expected plain_text in the third line, but found none"))?;
-        assert_eq!(plain_text3.as_rule(), Rule::plain_text);
-        assert_eq!(plain_text3.as_str(), "this one is too.");
+            .ok_or_else(|| anyhow!("Expected quote_line_text in the third line, but found none"))?;
+        assert_eq!(text3.as_str(), "this one is too.");
+
+        let html = str_to_html(input)?;
+        assert_eq!(
+            html[0],
+            "<blockquote><p>This is a text in a quote<br>this is also a part of a quote<br>this one is too.</p></blockquote>"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn check_nested_quote() -> Result<()> {
+        let input = "> ## Quote\n> - [x] item\n>\n> > nested\nlazy\n\nafter";
+        let html = str_to_html(input)?;
+        assert_eq!(
+            html,
+            vec![
+                "<blockquote><h2>Quote</h2>\
+                 <ul><li><input type=\"checkbox\" checked disabled> item</li></ul><br/>\
+                 <blockquote><p>nested<br>lazy</p></blockquote></blockquote>",
+                "<br/>",
+                "<p>after</p>",
+            ]
+        );
+
+        let tasks = parse_tasks(input)?;
+        assert_eq!(tasks.len(), 1);
+        assert_eq!(tasks[0].line, 2);
 
         Ok(())
     }
@@ -506,6 +1393,247 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn check_unordered_list() -> Result<()> {
+        let input = "- first **item**\n- second\n  + nested\n- third";
+        let mut pairs = parse_by_rule(Rule::unordered_list, input)?;
+
+        let list = pairs
+            .next()
+            .ok_or_else(|| anyhow!("Expected an unordered_list, but found none"))?;
+        assert_eq!(list.as_rule(), Rule::unordered_list);
+        assert_eq!(list.as_str(), input);
+
+        let items: Vec<_> = list.into_inner().collect();
+        assert_eq!(items.len(), 3);
+        assert!(items
+            .iter()
+            .all(|item| item.as_rule() == Rule::unordered_item));
+
+        let mut second_inner = items[1].clone().into_inner();
+        second_inner
+            .next()
+            .ok_or_else(|| anyhow!("Expected a bullet_marker, but found none"))?;
+        let text = second_inner
+            .next()
+            .ok_or_else(|| anyhow!("Expected list_item_text, but found none"))?;
+        assert_eq!(text.as_rule(), Rule::list_item_text);
+        assert_eq!(text.as_str(), "second\n");
+
+        let nested = second_inner
+            .next()
+            .ok_or_else(|| anyhow!("Expected a nested unordered_list, but found none"))?;
+        assert_eq!(nested.as_rule(), Rule::unordered_list);
+        assert_eq!(nested.into_inner().count(), 1);
+
+        // Another bullet marker starts a new list.
+        assert_eq!(
+            str_to_html("* a\n- b\n  + c\n  * d")?,
+            [
+                "<ul><li>a</li></ul>",
+                "<ul><li>b<ul><li>c</li></ul><ul><li>d</li></ul></li></ul>"
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn check_list_item_continuation() -> Result<()> {
+        assert_eq!(
+            str_to_html("- item\ncontinued")?,
+            ["<ul><li>item<br>continued</li></ul>"]
+        );
+        assert_eq!(str_to_html("- a\n  b")?, ["<ul><li>a<br>b</li></ul>"]);
+        assert_eq!(
+            str_to_html("- a\n\n  para in item")?,
+            ["<ul><li>a<p>para in item</p></li></ul>"]
+        );
+        assert_eq!(
+            str_to_html("1. a\n   - b\n\n   para\n\n   ```\n   code\n   ```\n2. c\n# Next")?,
+            [
+                "<ol><li>a<ul><li>b</li></ul><p>para</p><br/><pre><code>code</code></pre></li><li>c</li></ol>",
+                "<h1>Next</h1>"
+            ]
+        );
+
+        let document = parse_document("- a\n  b\n\n  [^n]\n\n[^n]: Note")?;
+        let Some(BlockKind::List(list)) = document.blocks.first().map(|block| &block.kind) else {
+            bail!("Expected a list");
+        };
+        let item = &list.items[0];
+        assert_eq!(
+            item.children
+                .iter()
+                .map(|inline| &inline.kind)
+                .collect::<Vec<_>>(),
+            [
+                &InlineKind::Text(String::from("a")),
+                &InlineKind::LineBreak,
+                &InlineKind::Text(String::from("b"))
+            ]
+        );
+        assert_eq!(item.blocks.len(), 1);
+        assert_eq!(item.blocks[0].span.start.line, 4);
+        assert_eq!(item.span.end.line, 4);
+
+        let formatted = format_markdown("* a\n  continued\n- b\n\n    para\n")?;
+        assert_eq!(formatted, "- a\n  continued\n\n* b\n\n  para\n");
+        assert_eq!(format_markdown(&formatted)?, formatted);
+
+        Ok(())
+    }
+
+    #[test]
+    fn check_ordered_list() -> Result<()> {
+        let html = str_to_html("3. first\n4) second\n   - nested")?;
+        assert_eq!(
+            html[0],
+            "<ol start=\"3\"><li>first</li><li>second<ul><li>nested</li></ul></li></ol>"
+        );
+
+        let html = str_to_html("Paragraph\n1. _item_")?;
+        assert_eq!(html[0], "<p>Paragraph</p>");
+        assert_eq!(html[1], "<ol><li><em>item</em></li></ol>");
+
+        Ok(())
+    }
+
+    #[test]
+    fn check_task_list() -> Result<()> {
+        let input = "# Release\n- [ ] Write changelog\n- [x] Bump **version**\n  - [X] Tag commit\n- Not a task";
+
+        let html = str_to_html(input)?;
+        assert_eq!(
+            html[1],
+            "<ul><li><input type=\"checkbox\" disabled> Write changelog</li>\
+             <li><input type=\"checkbox\" checked disabled> Bump <strong>version</strong>\
+             <ul><li><input type=\"checkbox\" checked disabled> Tag commit</li></ul></li>\
+             <li>Not a task</li></ul>"
+        );
+
+        let tasks = parse_tasks(input)?;
+        assert_eq!(
+            tasks,
+            vec![
+                TaskItem {
+                    text: String::from("Write changelog"),
+                    checked: false,
+                    line: 2,
+                },
+                TaskItem {
+                    text: String::from("Bump **version**"),
+                    checked: true,
+                    line: 3,
+                },
+                TaskItem {
+                    text: String::from("Tag commit"),
+                    checked: true,
+                    line: 4,
+                },
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn check_table() -> Result<()> {
+        let input = "| Name | Qty |\n|:---|---:|\n| a \\| b | **2** |\n| c |\n| d | e | f |";
+        let mut pairs = parse_by_rule(Rule::table, input)?;
+
+        let table = pairs
+            .next()
+            .ok_or_else(|| anyhow!("Expected a table, but found none"))?;
+        assert_eq!(table.as_rule(), Rule::table);
+
+        let mut table_inner = table.into_inner();
+        let header = table_inner
+            .next()
+            .ok_or_else(|| anyhow!("Expected a header table_row, but found none"))?;
+        assert_eq!(header.as_rule(), Rule::table_row);
+        let header_cells: Vec<&str> = header.into_inner().map(|cell| cell.as_str()).collect();
+        assert_eq!(header_cells, vec![" Name ", " Qty "]);
+
+        let delimiter = table_inner
+            .next()
+            .ok_or_else(|| anyhow!("Expected a table_delimiter_row, but found none"))?;
+        assert_eq!(delimiter.as_rule(), Rule::table_delimiter_row);
+        assert_eq!(delimiter.into_inner().count(), 2);
+        assert_eq!(table_inner.count(), 3);
+
+        let html = str_to_html(input)?;
+        assert_eq!(
+            html[0],
+            "<table><thead><tr><th style=\"text-align:left\">Name</th><th style=\"text-align:right\">Qty</th></tr></thead>\
+             <tbody><tr><td style=\"text-align:left\">a | b</td><td style=\"text-align:right\"><strong>2</strong></td></tr>\
+             <tr><td style=\"text-align:left\">c</td><td style=\"text-align:right\"></td></tr>\
+             <tr><td style=\"text-align:left\">d</td><td style=\"text-align:right\">e</td></tr></tbody></table>"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn check_footnotes() -> Result<()> {
+        let input = "Second[^b], first[^A] and again[^a].\nMissing[^x].\n\n[^a]: The _first_ note.\n[^b]: The second note.\n[^c]: Unused.";
+        let (html, warnings) = str_to_html_with_warnings(input)?;
+
+        assert_eq!(
+            html[0],
+            "<p>Second<sup class=\"footnote-ref\"><a href=\"#fn-1\" id=\"fnref-1\">1</a></sup>, \
+             first<sup class=\"footnote-ref\"><a href=\"#fn-2\" id=\"fnref-2\">2</a></sup> and \
+             again<sup class=\"footnote-ref\"><a href=\"#fn-2\" id=\"fnref-2-2\">2</a></sup>.<br>Missing[^x].</p>"
+        );
+        assert_eq!(
+            html.last().ok_or_else(|| anyhow!("Expected html lines, but found none"))?,
+            "<section class=\"footnotes\"><ol>\
+             <li id=\"fn-1\">The second note. <a href=\"#fnref-1\" class=\"footnote-backref\">↩</a></li>\
+             <li id=\"fn-2\">The <em>first</em> note. <a href=\"#fnref-2\" class=\"footnote-backref\">↩</a> \
+             <a href=\"#fnref-2-2\" class=\"footnote-backref\">↩</a></li></ol></section>"
+        );
+        assert_eq!(
+            warnings,
+            vec![
+                ParseWarning::UndefinedFootnote {
+                    label: String::from("x"),
+                    line: 2,
+                },
+                ParseWarning::UnusedFootnote {
+                    label: String::from("c"),
+                    line: 6,
+                },
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn check_code_span() -> Result<()> {
+        let input = "``a `*b*` \\c``";
+        let mut pairs = parse_by_rule(Rule::code_span, input)?;
+        let pair = pairs
+            .next()
+            .ok_or_else(|| anyhow!("Expected a code_span, but found none"))?;
+        assert_eq!(pair.as_rule(), Rule::code_span);
+
+        let content = pair
+            .into_inner()
+            .next()
+            .ok_or_else(|| anyhow!("Expected code_span_content, but found none"))?;
+        assert_eq!(content.as_rule(), Rule::code_span_content);
+        assert_eq!(content.as_str(), "a `*b*` \\c");
+
+        let html = str_to_html("Call ` _f_ ` or `` ` `` with `<T>` and ``` lone` backtick")?;
+        assert_eq!(
+            html[0],
+            "<p>Call <code>_f_</code> or <code>`</code> with <code>&lt;T&gt;</code> and ``` lone` backtick</p>"
+        );
+
+        Ok(())
+    }
+
     #[test]
     fn check_markdown() -> Result<()> {
         let input = "# Hello this is my 1st post!\n–––\n\nThis code prints \"Hello world\":\n```py\nprint(\"Hello world!\")\n```\n\nThis is **bold** text!\nThat's all. Bye!";